@@ -17,6 +17,38 @@ pub(crate) struct Cli {
     #[arg(long)]
     pub dept: Option<usize>,
 
+    /// The number of sub fragments of each fragment at every level of the dept of the "tree" fragmentation
+    #[arg(long, default_value_t = 2)]
+    pub branching_factor: usize,
+
+    /// The placement of the members among the fragments of the "tree" fragmentation covering their date
+    /// can be either "random", "leafOnly" or "deepestMatching" [default: "random"]
+    #[arg(long)]
+    pub placement: Option<String>,
+
+    /// The seed of the "random" placement so that the fragmentation is reproducible,
+    /// by default the seed is drawn from the entropy of the system [default: None]
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// The maximum number of children of the nodes of the B+tree fragmentation
+    #[arg(long, default_value_t = 32)]
+    pub fan_out: usize,
+
+    /// The number of members targeted by leaf of the B+tree fragmentation
+    #[arg(long, default_value_t = 1_000)]
+    pub page_size: usize,
+
+    /// If set a fragment holding more members is split in two fragments on the median date of its members,
+    /// it keeps only the relations to them [default: None]
+    #[arg(long)]
+    pub max_members_per_fragment: Option<usize>,
+
+    /// The fraction of the average number of members by fragment below which a fragment is merged
+    /// with an adjacent fragment by the "linkedList" and "oneAryTree" fragmentations
+    #[arg(long, default_value_t = 0.1)]
+    pub fill_factor: f64,
+
     /// Path of the configuration file
     /// By default the value is [default: ./config.json]
     #[arg(short, long)]
@@ -27,16 +59,53 @@ pub(crate) struct Cli {
     #[arg(short, long)]
     pub output_path: Option<PathBuf>,
 
-    /// Path of the data dump
+    /// Paths or glob patterns of the data dumps, they can be compressed with gzip, bzip2 or zstd.
+    /// The data dumps are parsed one after the other into the same collection, "-" reads the standard input
     /// By default is [default: ../comunica_filter_benchmark/evaluation/data/dahcc_1_participant/data.ttl]
-    #[arg(short, long)]
-    pub data_dump_path: Option<PathBuf>,
+    #[arg(short, long, num_args = 1..)]
+    pub data_dump_path: Vec<String>,
 
     /// If set to false will put the whole file in memory, with false will read the file line by line [default: false]
     #[arg(short, long,default_value_t=false, action = clap::ArgAction::SetTrue)]
     pub large_file: bool,
 
-    /// The the type of fragmentation can be either "linkedList", "oneAryTree" or "tree" [default: "oneAryTree"]
+    /// The the type of fragmentation can be either "linkedList", "oneAryTree", "tree" or "bPlusTree" [default: "oneAryTree"],
+    /// the B+tree is built from the distribution of the dates found with --pre-scan
     #[arg(short, long)]
     pub fragmentation: Option<String>,
+
+    /// If set the collection is published as an `ldes:EventStream` [default: false]
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub ldes: bool,
+
+    /// How the members are delimited in the data dump can be either "schemaComplete", "subjectGrouped", "cbd" or "namedGraph" [default: "schemaComplete"]
+    #[arg(short, long)]
+    pub member_extraction: Option<String>,
+
+    /// The RDF serialization of the data dumps can be either "turtle", "nTriples", "nQuads" or "trig",
+    /// by default it is guessed from the extension of each file and otherwise is [default: "turtle"]
+    #[arg(long)]
+    pub input_format: Option<String>,
+
+    /// If set the members are written in their named graph and the fragments are TriG documents [default: false]
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub output_named_graphs: bool,
+
+    /// If set the data dumps are streamed once before the fragmentation to find the number of members,
+    /// the lowest date and the highest date when they are not defined in the config [default: false]
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub pre_scan: bool,
+
+    /// If set the statistics found by the pre-scan are written back in the config file [default: false]
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub update_config: bool,
+
+    /// If set the malformed statements of the data dumps are logged and skipped instead of
+    /// aborting the parsing, the lines for N-Triples and N-Quads and the statements for Turtle and TriG [default: false]
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    pub lenient: bool,
+
+    /// The number of malformed statements skipped in lenient mode before the parsing is aborted
+    #[arg(long, default_value_t = 100)]
+    pub max_syntax_errors: usize,
 }
@@ -0,0 +1,52 @@
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+/// The compression of a data dump
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Bzip2,
+    Zstd,
+}
+
+impl Compression {
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "gz" => Some(Self::Gzip),
+            "bz2" => Some(Self::Bzip2),
+            "zst" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    pub fn from_magic_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            Some(Self::Gzip)
+        } else if bytes.starts_with(b"BZh") {
+            Some(Self::Bzip2)
+        } else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Self::Zstd)
+        } else {
+            None
+        }
+    }
+}
+
+/// Decompress the data dump while it is read, the compression is guessed from the extension
+/// of the file and otherwise from its first bytes. The reader is returned as is if it is not compressed.
+pub fn decompress(mut reader: Box<dyn BufRead>, path: &Path) -> io::Result<Box<dyn BufRead>> {
+    let compression = match Compression::from_extension(path) {
+        Some(compression) => Some(compression),
+        None => Compression::from_magic_bytes(reader.fill_buf()?),
+    };
+    Ok(match compression {
+        Some(Compression::Gzip) => {
+            Box::new(BufReader::new(flate2::bufread::MultiGzDecoder::new(reader)))
+        }
+        Some(Compression::Bzip2) => {
+            Box::new(BufReader::new(bzip2::bufread::MultiBzDecoder::new(reader)))
+        }
+        Some(Compression::Zstd) => Box::new(BufReader::new(zstd::Decoder::with_buffer(reader)?)),
+        None => reader,
+    })
+}
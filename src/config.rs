@@ -1,41 +1,93 @@
-use chrono;
-use serde;
+use crate::error::Error;
 use std::collections::HashSet;
-use std::fs::read_to_string;
+use std::fs::{read_to_string, write};
 use std::path::PathBuf;
 
 /// Configuration of the parser, the member IRI must respect a regex expression
-#[derive(serde::Deserialize, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct Config {
     /// A regex that the IRI of the members must respect
     pub member_url_regex: String,
     /// The [Schema]s of the member property
+    #[serde(default)]
     pub schema: Vec<Schema>,
-    /// The number of members, those not have any impact on the execution it is for being shown to the user
-    pub n_members: usize,
-    /// The date field for the fragmentation
-    pub date_field: String,
-    /// The highest date present in the data dump
-    pub highest_date: chrono::NaiveDateTime,
-    /// The lowest date present in the data dump
-    pub lowest_date: chrono::NaiveDateTime,
+    /// The number of members, it is used to size the member cache of the fragments and to show the progress,
+    /// it can be discovered by a pre-scan of the data dump
+    pub n_members: Option<usize>,
+    /// The date fields for the fragmentation in priority order, the date of a member is the object
+    /// of its first date field present. It is a single IRI or a list of IRIs, the first one is
+    /// the path published in the relations and as the timestamp path.
+    #[serde(
+        deserialize_with = "deserialize_date_field",
+        serialize_with = "serialize_date_field"
+    )]
+    pub date_field: Vec<String>,
+    /// The formats (in the chrono syntax) tried in order when the date is not
+    /// a xsd date, a xsd date time or a number of seconds since the Unix epoch
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub date_formats: Vec<String>,
+    /// The highest date present in the data dump, it can be discovered by a pre-scan of the data dump
+    pub highest_date: Option<chrono::NaiveDateTime>,
+    /// The lowest date present in the data dump, it can be discovered by a pre-scan of the data dump
+    pub lowest_date: Option<chrono::NaiveDateTime>,
     /// The address of the server that will host the TREE document
     pub server_address: String,
+    /// The path to the object a member is a version of, published as
+    /// `ldes:versionOfPath` when the output is an LDES
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_of_path: Option<String>,
+    /// The depth up to which the named nodes are followed by the CBD member extraction,
+    /// the blank nodes are always followed
+    #[serde(default)]
+    pub cbd_depth: usize,
+    /// The path of a SHACL shapes file, the members are validated with its node shape
+    /// and the shape is published with the collection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shape_path: Option<PathBuf>,
+    /// With the named graph member extraction, the id of a member is the subject of the triple
+    /// with this predicate inside its named graph instead of the name of the graph
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub named_graph_member_predicate: Option<String>,
+    /// Where the id of the members is taken from, by default it is the IRI found by the member extraction
+    #[serde(default)]
+    pub member_id: MemberIdSource,
 }
 impl Config {
-    pub fn new(config_path: PathBuf) -> Self {
-        let data = read_to_string(config_path).unwrap();
-        let config: Config = serde_json::from_str(data.as_str()).unwrap();
-        config
+    pub fn new(config_path: PathBuf) -> Result<Self, Error> {
+        let data = read_to_string(&config_path).map_err(|e| Error::io(&config_path, e))?;
+        let config: Config = serde_json::from_str(data.as_str()).map_err(|e| {
+            Error::Config(format!(
+                "the config {} is not valid: {e}",
+                config_path.display()
+            ))
+        })?;
+        if config.date_field.is_empty() {
+            return Err(Error::Config(format!(
+                "the config {} should define at least one date field",
+                config_path.display()
+            )));
+        }
+        Ok(config)
+    }
+
+    /// The date field with the highest priority
+    pub fn primary_date_field(&self) -> &String {
+        &self.date_field[0]
+    }
+
+    pub fn save(&self, config_path: PathBuf) -> Result<(), Error> {
+        let data = serde_json::to_string_pretty(self)
+            .map_err(|e| Error::Config(format!("the config cannot be serialized: {e}")))?;
+        write(&config_path, data).map_err(|e| Error::io(&config_path, e))
     }
 }
 
 /// The schema of a triple pattern associated with a TREE member
-#[derive(serde::Deserialize, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct Schema {
     pub subject: SubjectDescriptor,
     pub predicate: String,
-    pub object: Option<SubjectDescriptor>,
+    pub object: Option<ObjectDescriptor>,
 }
 impl Schema {
     /// check if a triple is valid with the schema
@@ -53,16 +105,60 @@ impl Schema {
             }
         };
 
-        if self.predicate != input.predicate {
+        if !is_same_iri(&self.predicate, &input.predicate) {
             return false;
         }
 
-        return true;
+        match &self.object {
+            Some(ObjectDescriptor::Iri { iri }) => is_same_iri(iri, &input.object),
+            Some(ObjectDescriptor::Datatype { datatype }) => input
+                .object_datatype
+                .as_ref()
+                .is_some_and(|object_datatype| is_same_iri(datatype, object_datatype)),
+            Some(ObjectDescriptor::Regex { regex }) => regex.is_match(&input.object_value),
+            Some(ObjectDescriptor::LinkedSubject { subject: _ }) | None => true,
+        }
+    }
+
+    /// The label given to the object of the triple when the schema declares it as a linked subject
+    pub fn linked_subject_label(&self) -> Option<&String> {
+        if let Some(ObjectDescriptor::LinkedSubject { subject }) = &self.object {
+            Some(subject)
+        } else {
+            None
+        }
     }
 }
 
+/// Compare two IRIs whether or not they are written between angle brackets
+pub fn is_same_iri(first: &str, second: &str) -> bool {
+    first.trim_start_matches('<').trim_end_matches('>')
+        == second.trim_start_matches('<').trim_end_matches('>')
+}
+
+/// The source of the id of the members, the triples of a member are rewritten with its id
+/// unless it is its subject or its named graph
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Default)]
+pub enum MemberIdSource {
+    /// The IRI found by the member extraction, the subject respecting the member IRI regex
+    /// or the named graph
+    #[default]
+    Subject,
+    /// The IRI captured by a group of the member IRI regex,
+    /// a relative IRI is resolved against the server address
+    RegexCaptureGroup { group: usize },
+    /// The object of the triple of the member with this predicate, the value of a literal
+    /// is resolved against the server address
+    Predicate { predicate: String },
+    /// The name of the named graph of the member
+    GraphName,
+    /// An IRI generated from a template, `{server}` is the server address without its final slash
+    /// and `{n}` the number of the member starting from 1, e.g. `{server}/member/{n}`
+    Template { template: String },
+}
+
 /// A descriptor for a subject in the context of a triple
-#[derive(serde::Deserialize, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub enum SubjectDescriptor {
     /// The subject is the member
     MemberSubject,
@@ -70,11 +166,110 @@ pub enum SubjectDescriptor {
     LinkedSubject { subject: String },
 }
 
+/// A descriptor for an object in the context of a triple
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub enum ObjectDescriptor {
+    /// The object is exactly this IRI
+    Iri { iri: String },
+    /// The object is a literal of this datatype
+    Datatype { datatype: String },
+    /// The value of the object (the IRI or the lexical form of the literal) respects a regex
+    Regex {
+        #[serde(
+            deserialize_with = "deserialize_regex",
+            serialize_with = "serialize_regex"
+        )]
+        regex: regex::Regex,
+    },
+    /// The object is a property or subproperty of the member, it is labelled
+    /// so that it can be described by a [SubjectDescriptor::LinkedSubject]
+    LinkedSubject { subject: String },
+}
+
+fn deserialize_regex<'de, D>(deserializer: D) -> Result<regex::Regex, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let regex: String = serde::Deserialize::deserialize(deserializer)?;
+    regex::Regex::new(&regex).map_err(serde::de::Error::custom)
+}
+
+fn serialize_regex<S>(regex: &regex::Regex, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(regex.as_str())
+}
+
+/// The date field is a single IRI or a list of IRIs
+fn deserialize_date_field<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum DateField {
+        Single(String),
+        List(Vec<String>),
+    }
+    Ok(match serde::Deserialize::deserialize(deserializer)? {
+        DateField::Single(date_field) => vec![date_field],
+        DateField::List(date_fields) => date_fields,
+    })
+}
+
+fn serialize_date_field<S>(date_field: &Vec<String>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match date_field.as_slice() {
+        [date_field] => serializer.serialize_str(date_field),
+        date_fields => serde::Serialize::serialize(date_fields, serializer),
+    }
+}
+
 /// Input argument of the [Schema] [Schema::is_valid]
+#[derive(Clone)]
 pub struct SchemaValidatorInput {
     pub subject: String,
     pub predicate: String,
     pub object: String,
+    /// The IRI or the lexical form of the object
+    pub object_value: String,
+    /// The datatype of the object if it is a literal
+    pub object_datatype: Option<String>,
     pub member_id: String,
     pub related_subject: HashSet<String>,
 }
+
+impl SchemaValidatorInput {
+    pub fn from_triple(triple: &rio_api::model::Triple) -> Self {
+        let (object_value, object_datatype) = match triple.object {
+            rio_api::model::Term::NamedNode(node) => (node.iri.to_string(), None),
+            rio_api::model::Term::BlankNode(node) => (node.id.to_string(), None),
+            rio_api::model::Term::Literal(rio_api::model::Literal::Simple { value }) => {
+                (value.to_string(), Some(XSD_STRING_VOCAB.to_string()))
+            }
+            rio_api::model::Term::Literal(rio_api::model::Literal::LanguageTaggedString {
+                value,
+                language: _,
+            }) => (value.to_string(), Some(RDF_LANG_STRING_VOCAB.to_string())),
+            rio_api::model::Term::Literal(rio_api::model::Literal::Typed { value, datatype }) => {
+                (value.to_string(), Some(datatype.iri.to_string()))
+            }
+            rio_api::model::Term::Triple(_) => (triple.object.to_string(), None),
+        };
+        Self {
+            subject: triple.subject.to_string(),
+            predicate: triple.predicate.to_string(),
+            object: triple.object.to_string(),
+            object_value,
+            object_datatype,
+            member_id: String::new(),
+            related_subject: HashSet::new(),
+        }
+    }
+}
+
+const XSD_STRING_VOCAB: &str = "http://www.w3.org/2001/XMLSchema#string";
+const RDF_LANG_STRING_VOCAB: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
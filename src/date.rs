@@ -0,0 +1,244 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+/// Parse the date of a member from a literal.
+/// The xsd date and date time datatypes are parsed with their time zone normalized to UTC,
+/// the numeric literals are seconds since the Unix epoch and the literals without a date datatype
+/// are tried in those forms. When none of them apply the fallback formats are tried in order.
+pub fn parse_date(
+    literal: &rio_api::model::Literal,
+    fallback_formats: &Vec<String>,
+) -> Result<DateTime<Utc>, String> {
+    let (value, datatype) = match literal {
+        rio_api::model::Literal::Simple { value } => (*value, None),
+        rio_api::model::Literal::LanguageTaggedString { value, language: _ } => (*value, None),
+        rio_api::model::Literal::Typed { value, datatype } => (*value, Some(datatype.iri)),
+    };
+    let value = value.trim();
+    let date = match datatype {
+        Some(XSD_DATE_TIME_VOCAB) | Some(XSD_DATE_TIME_STAMP_VOCAB) => parse_xsd_date_time(value),
+        Some(XSD_DATE_VOCAB) => parse_xsd_date(value),
+        Some(datatype) if XSD_NUMERIC_VOCABS.contains(&datatype) => parse_epoch(value),
+        _ => parse_xsd_date_time(value)
+            .or_else(|| parse_xsd_date(value))
+            .or_else(|| parse_epoch(value)),
+    };
+    date.or_else(|| parse_with_formats(value, fallback_formats))
+        .ok_or(format!(
+            "the date {literal} is not an xsd date, an epoch or in one of the formats {fallback_formats:?}"
+        ))
+}
+
+/// Place a date on the time axis of the members and of the fragments,
+/// it is in nanoseconds since the Unix epoch and covers the years 1677 to 2262
+pub fn to_time_axis(date: &NaiveDateTime) -> Result<i64, String> {
+    let nanoseconds = date.timestamp() as i128 * NANOSECONDS_PER_SECOND as i128
+        + date.timestamp_subsec_nanos() as i128;
+    i64::try_from(nanoseconds).map_err(|_| format!("the date {date} is out of the time axis"))
+}
+
+/// The date of a position on the time axis
+pub fn from_time_axis(position: i64) -> NaiveDateTime {
+    NaiveDateTime::from_timestamp_opt(
+        position.div_euclid(NANOSECONDS_PER_SECOND),
+        position.rem_euclid(NANOSECONDS_PER_SECOND) as u32,
+    )
+    .unwrap()
+}
+
+/// Parse a `xsd:dateTime` or a `xsd:dateTimeStamp`, without a time zone the date is in UTC
+fn parse_xsd_date_time(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|date| Utc.from_utc_datetime(&date))
+}
+
+/// Parse a `xsd:date` as its first instant, with its optional time zone
+fn parse_xsd_date(value: &str) -> Option<DateTime<Utc>> {
+    if value.len() < XSD_DATE_LENGTH || !value.is_char_boundary(XSD_DATE_LENGTH) {
+        return None;
+    }
+    let (date, time_zone) = value.split_at(XSD_DATE_LENGTH);
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    parse_xsd_date_time(&format!("{date}T00:00:00{time_zone}"))
+}
+
+/// Parse a number of seconds since the Unix epoch, the decimals are kept up to the nanosecond
+fn parse_epoch(value: &str) -> Option<DateTime<Utc>> {
+    let (seconds, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if let (Ok(seconds), true) = (
+        seconds.parse::<i64>(),
+        fraction.chars().all(|c| c.is_ascii_digit()),
+    ) {
+        let nanoseconds: i64 = format!("{:0<9}", &fraction[..fraction.len().min(9)])
+            .parse()
+            .ok()?;
+        let nanoseconds = if value.starts_with('-') {
+            -nanoseconds
+        } else {
+            nanoseconds
+        };
+        return Utc
+            .timestamp_opt(seconds, 0)
+            .single()?
+            .checked_add_signed(Duration::nanoseconds(nanoseconds));
+    }
+    // the doubles can be written with an exponent
+    let seconds = value.parse::<f64>().ok()?;
+    if !seconds.is_finite() {
+        return None;
+    }
+    Utc.timestamp_opt(
+        seconds.floor() as i64,
+        ((seconds - seconds.floor()) * 1e9) as u32,
+    )
+    .single()
+}
+
+/// Parse a date with the first format that matches it, the formats without a time zone are in UTC
+/// and the formats without a time are the first instant of the day
+fn parse_with_formats(value: &str, formats: &Vec<String>) -> Option<DateTime<Utc>> {
+    for format in formats {
+        if let Ok(date) = DateTime::parse_from_str(value, format) {
+            return Some(date.with_timezone(&Utc));
+        }
+        if let Ok(date) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(Utc.from_utc_datetime(&date));
+        }
+        if let Ok(date) = NaiveDate::parse_from_str(value, format) {
+            return Some(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?));
+        }
+    }
+    None
+}
+
+const NANOSECONDS_PER_SECOND: i64 = 1_000_000_000;
+const XSD_DATE_LENGTH: usize = "YYYY-MM-DD".len();
+
+const XSD_DATE_TIME_VOCAB: &str = "http://www.w3.org/2001/XMLSchema#dateTime";
+const XSD_DATE_TIME_STAMP_VOCAB: &str = "http://www.w3.org/2001/XMLSchema#dateTimeStamp";
+const XSD_DATE_VOCAB: &str = "http://www.w3.org/2001/XMLSchema#date";
+const XSD_NUMERIC_VOCABS: [&str; 9] = [
+    "http://www.w3.org/2001/XMLSchema#integer",
+    "http://www.w3.org/2001/XMLSchema#long",
+    "http://www.w3.org/2001/XMLSchema#int",
+    "http://www.w3.org/2001/XMLSchema#nonNegativeInteger",
+    "http://www.w3.org/2001/XMLSchema#positiveInteger",
+    "http://www.w3.org/2001/XMLSchema#unsignedLong",
+    "http://www.w3.org/2001/XMLSchema#decimal",
+    "http://www.w3.org/2001/XMLSchema#double",
+    "http://www.w3.org/2001/XMLSchema#float",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed<'a>(value: &'a str, datatype: &'a str) -> rio_api::model::Literal<'a> {
+        rio_api::model::Literal::Typed {
+            value,
+            datatype: rio_api::model::NamedNode { iri: datatype },
+        }
+    }
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn parse_date_time_normalizes_the_time_zone_to_utc() {
+        let date = parse_date(
+            &typed("2022-01-01T01:30:00+02:00", XSD_DATE_TIME_VOCAB),
+            &vec![],
+        );
+        assert_eq!(date, Ok(utc("2021-12-31T23:30:00Z")));
+    }
+
+    #[test]
+    fn parse_date_time_without_time_zone_is_in_utc() {
+        let date = parse_date(
+            &typed("2022-01-01T10:00:00.5", XSD_DATE_TIME_VOCAB),
+            &vec![],
+        );
+        assert_eq!(date, Ok(utc("2022-01-01T10:00:00.5Z")));
+    }
+
+    #[test]
+    fn parse_date_is_the_first_instant_of_the_day() {
+        assert_eq!(
+            parse_date(&typed("2022-03-04", XSD_DATE_VOCAB), &vec![]),
+            Ok(utc("2022-03-04T00:00:00Z"))
+        );
+        assert_eq!(
+            parse_date(&typed("2022-03-04-05:00", XSD_DATE_VOCAB), &vec![]),
+            Ok(utc("2022-03-04T05:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn parse_epoch_keeps_the_nanoseconds() {
+        assert_eq!(
+            parse_date(
+                &typed(
+                    "1640995200.123456789",
+                    "http://www.w3.org/2001/XMLSchema#decimal"
+                ),
+                &vec![]
+            ),
+            Ok(utc("2022-01-01T00:00:00.123456789Z"))
+        );
+        assert_eq!(parse_epoch("-1.5"), Some(utc("1969-12-31T23:59:58.5Z")));
+        assert_eq!(
+            parse_epoch("1.6409952E9"),
+            Some(utc("2022-01-01T00:00:00Z"))
+        );
+        assert_eq!(parse_epoch("NaN"), None);
+    }
+
+    #[test]
+    fn parse_simple_literal_tries_every_form() {
+        let simple = |value| rio_api::model::Literal::Simple { value };
+        assert_eq!(
+            parse_date(&simple("2022-01-01T00:00:00Z"), &vec![]),
+            Ok(utc("2022-01-01T00:00:00Z"))
+        );
+        assert_eq!(
+            parse_date(&simple("2022-01-01"), &vec![]),
+            Ok(utc("2022-01-01T00:00:00Z"))
+        );
+        assert_eq!(
+            parse_date(&simple(" 1640995200 "), &vec![]),
+            Ok(utc("2022-01-01T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn parse_with_the_first_matching_fallback_format() {
+        let formats = vec!["%d/%m/%Y %H:%M".to_string(), "%d/%m/%Y".to_string()];
+        let simple = |value| rio_api::model::Literal::Simple { value };
+        assert_eq!(
+            parse_date(&simple("04/03/2022 10:15"), &formats),
+            Ok(utc("2022-03-04T10:15:00Z"))
+        );
+        assert_eq!(
+            parse_date(&simple("04/03/2022"), &formats),
+            Ok(utc("2022-03-04T00:00:00Z"))
+        );
+        assert!(parse_date(&simple("garbage"), &formats).is_err());
+    }
+
+    #[test]
+    fn the_time_axis_round_trips() {
+        let date = utc("1999-12-31T23:59:59.999999999Z").naive_utc();
+        let position = to_time_axis(&date).unwrap();
+        assert_eq!(from_time_axis(position), date);
+        let before_epoch = utc("1969-12-31T23:59:59.5Z").naive_utc();
+        assert_eq!(to_time_axis(&before_epoch), Ok(-500_000_000));
+        assert_eq!(from_time_axis(-500_000_000), before_epoch);
+        assert!(to_time_axis(&utc("2300-01-01T00:00:00Z").naive_utc()).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use rio_api::parser::ParseError;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The failures of the fragmentation of a data dump, each kind of failure exits the program
+/// with its own [Error::exit_code]
+#[derive(Debug)]
+pub enum Error {
+    /// The config file or the arguments of the command line are not valid
+    Config(String),
+    /// A file cannot be read or written
+    Io {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+    /// A data dump or a shapes file is not a valid RDF document
+    RdfSyntax {
+        path: Option<PathBuf>,
+        /// The line of the error starting from 1
+        line: Option<u64>,
+        /// The byte of the error in its line starting from 1
+        column: Option<u64>,
+        message: String,
+    },
+    /// The members of a data dump do not have the form expected by the config
+    Schema {
+        path: Option<PathBuf>,
+        message: String,
+    },
+    /// The fragments cannot be created or written
+    Fragmentation(String),
+}
+
+impl Error {
+    /// The exit code of the program when it fails with this error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Config(_) => 2,
+            Self::Io { .. } => 3,
+            Self::RdfSyntax { .. } => 4,
+            Self::Schema { .. } => 5,
+            Self::Fragmentation(_) => 6,
+        }
+    }
+
+    /// An IO error on a file
+    pub fn io(path: &Path, source: io::Error) -> Self {
+        Self::Io {
+            path: Some(path.to_path_buf()),
+            source,
+        }
+    }
+
+    /// Locate the error in a file when it is not already
+    pub fn in_file(self, file_path: &Path) -> Self {
+        match self {
+            Self::Io { path: None, source } => Self::io(file_path, source),
+            Self::RdfSyntax {
+                path: None,
+                line,
+                column,
+                message,
+            } => Self::RdfSyntax {
+                path: Some(file_path.to_path_buf()),
+                line,
+                column,
+                message,
+            },
+            Self::Schema {
+                path: None,
+                message,
+            } => Self::Schema {
+                path: Some(file_path.to_path_buf()),
+                message,
+            },
+            error => error,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Config(message) => write!(f, "invalid configuration: {message}"),
+            Self::Io { path, source } => {
+                write!(f, "IO error")?;
+                if let Some(path) = path {
+                    write!(f, " on {}", path.display())?;
+                }
+                write!(f, ": {source}")
+            }
+            Self::RdfSyntax {
+                path,
+                line,
+                column,
+                message,
+            } => {
+                write!(f, "RDF syntax error")?;
+                if let Some(path) = path {
+                    write!(f, " in {}", path.display())?;
+                }
+                if let Some(line) = line {
+                    write!(f, " on line {line}")?;
+                }
+                if let Some(column) = column {
+                    write!(f, " at byte {column}")?;
+                }
+                write!(f, ": {message}")
+            }
+            Self::Schema { path, message } => {
+                write!(f, "schema error")?;
+                if let Some(path) = path {
+                    write!(f, " in {}", path.display())?;
+                }
+                write!(f, ": {message}")
+            }
+            Self::Fragmentation(message) => write!(f, "fragmentation error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { path: _, source } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(source: io::Error) -> Self {
+        Self::Io { path: None, source }
+    }
+}
+
+impl From<rio_turtle::TurtleError> for Error {
+    fn from(error: rio_turtle::TurtleError) -> Self {
+        let position = error.textual_position();
+        if position.is_none()
+            && std::error::Error::source(&error).is_some_and(|source| source.is::<io::Error>())
+        {
+            return Self::from(io::Error::from(error));
+        }
+        // the position is already given by the fields of the error
+        let message = error.to_string();
+        let message = match message.rfind(" on line ") {
+            Some(i) if position.is_some() => message[..i].to_string(),
+            _ => message,
+        };
+        Self::RdfSyntax {
+            path: None,
+            line: position.map(|position| position.line_number()),
+            column: position.map(|position| position.byte_number()),
+            message,
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use super::fragment::*;
+use crate::error::Error;
+use crate::member::Member;
+use crate::tree::Collection;
+use async_trait;
+use std::path::PathBuf;
+
+/// The shape of a B+tree and the distribution of the dates it is built from
+#[derive(Clone, Debug)]
+pub struct BPlusTreeParameters {
+    /// The maximum number of children of the root and of the inner nodes
+    pub fan_out: usize,
+    /// The number of members targeted by leaf
+    pub page_size: usize,
+    pub n_members: usize,
+    /// A sorted sample of the dates of the members
+    pub date_sample: Vec<i64>,
+}
+
+/// The members are in the leaves of a balanced tree, the boundaries of the leaves are
+/// the quantiles of the dates of the members so that each leaf holds about a page of members
+/// whatever the distribution of the dates. The members sharing the same date cannot be split,
+/// so a leaf can exceed the page size when a date is very frequent. The leaves are grouped by
+/// inner nodes of at most the fan-out children up to the root node, every level is linked
+/// to the next with `tree:GreaterThanOrEqualToRelation` and `tree:LessThanRelation`.
+pub struct BPlusTreeFragmentation {
+    /// the leaves ordered by date
+    leaves: Vec<Fragment>,
+    max_size_cache: usize,
+    folder: PathBuf,
+    server_address: String,
+    fragmentation_property: String,
+}
+
+impl BPlusTreeFragmentation {
+    pub async fn new(
+        max_size_cache: usize,
+        folder: &PathBuf,
+        server_address: String,
+        fragmentation_property: String,
+        parameters: BPlusTreeParameters,
+        max_members_per_fragment: Option<usize>,
+        collection: Collection,
+    ) -> Result<Self, Error> {
+        if parameters.fan_out < 2 {
+            return Err(Error::Config(
+                "the fan-out of the B+tree should be at least 2".to_string(),
+            ));
+        }
+        if parameters.page_size == 0 {
+            return Err(Error::Config(
+                "the page size of the B+tree should be at least 1".to_string(),
+            ));
+        }
+        let node_path = |i: usize| {
+            let mut resp = folder.clone();
+            resp.push(collection.node_filename(i));
+            resp
+        };
+
+        let lower_bounds = leaf_lower_bounds(
+            &parameters.date_sample,
+            parameters.n_members.div_ceil(parameters.page_size),
+        );
+        let mut leaves = Vec::with_capacity(lower_bounds.len());
+        for (i, lower_bound) in lower_bounds.iter().enumerate() {
+            leaves.push(
+                Fragment::new(
+                    node_path(i + 1),
+                    max_size_cache,
+                    *lower_bound,
+                    lower_bounds.get(i + 1).copied().unwrap_or(i64::MAX),
+                    collection.clone(),
+                )
+                .await?,
+            );
+        }
+        for leaf in leaves.iter_mut() {
+            leaf.set_max_members(max_members_per_fragment);
+        }
+
+        let mut level = leaves.clone();
+        let mut n_nodes = leaves.len();
+        while level.len() > parameters.fan_out {
+            let n_parents = level.len().div_ceil(parameters.fan_out);
+            let mut next_level = Vec::with_capacity(n_parents);
+            // the children are spread evenly so that every inner node is at least half full
+            for j in 0..n_parents {
+                let children =
+                    &level[j * level.len() / n_parents..(j + 1) * level.len() / n_parents];
+                n_nodes += 1;
+                let parent = Fragment::new(
+                    node_path(n_nodes),
+                    max_size_cache,
+                    children[0].boundary().lower,
+                    children[children.len() - 1].boundary().upper,
+                    collection.clone(),
+                )
+                .await?;
+                let parent_name = parent.node_name()?;
+                let mut relations = Vec::new();
+                for child in children.iter() {
+                    relations.append(&mut child.boundary().to_relation(
+                        &parent_name,
+                        &child.node_name()?,
+                        &fragmentation_property,
+                        &server_address,
+                    ));
+                }
+                parent.materialize_relation(relations).await?;
+                next_level.push(parent);
+            }
+            level = next_level;
+        }
+        super::generate_central_root_node(
+            folder,
+            level.len(),
+            &level,
+            &fragmentation_property,
+            &server_address,
+            &collection,
+        )?;
+
+        super::create_report(&leaves, folder)?;
+        Ok(Self {
+            leaves,
+            max_size_cache,
+            folder: folder.clone(),
+            server_address,
+            fragmentation_property,
+        })
+    }
+}
+
+/// The lower bounds of the leaves, the first leaf is open on the left and the duplicated quantiles
+/// are merged, so there can be less leaves than requested
+fn leaf_lower_bounds(date_sample: &[i64], n_leaves: usize) -> Vec<i64> {
+    let mut resp = vec![i64::MIN];
+    for k in 1..n_leaves {
+        if let Some(quantile) = date_sample.get(k * date_sample.len() / n_leaves) {
+            if *quantile > resp[resp.len() - 1] {
+                resp.push(*quantile);
+            }
+        }
+    }
+    resp
+}
+
+#[async_trait::async_trait]
+impl super::Fragmentation for BPlusTreeFragmentation {
+    async fn insert(&mut self, member: &Member) -> Result<(), Error> {
+        // the lower bound of a leaf is inclusive and its upper bound exclusive
+        let pos = self
+            .leaves
+            .partition_point(|leaf| leaf.boundary().lower <= member.date)
+            .saturating_sub(1);
+        if self.leaves[pos].insert(member).is_err() {
+            self.leaves[pos].materialize().await?;
+            self.leaves[pos]
+                .insert(member)
+                .map_err(|e| Error::Fragmentation(e.to_string()))?;
+        }
+        // a leaf holding too many members becomes an inner node of its two halves
+        super::split_full_fragment(
+            &mut self.leaves,
+            pos,
+            &self.fragmentation_property,
+            &self.server_address,
+        )
+        .await?;
+        Ok(())
+    }
+    async fn finalize(&mut self) -> Result<(), Error> {
+        super::materialize_fragments(&mut self.leaves).await?;
+        self.print_summary();
+        super::create_report(&self.leaves, &self.folder)
+    }
+    fn max_size_cache(&self) -> usize {
+        self.max_size_cache
+    }
+    fn fragments(&self) -> &Vec<Fragment> {
+        &self.leaves
+    }
+}
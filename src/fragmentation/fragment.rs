@@ -1,3 +1,5 @@
+use crate::date::from_time_axis;
+use crate::error::Error;
 use crate::member::Member;
 use crate::tree::*;
 use chrono;
@@ -5,7 +7,8 @@ use serde;
 use std::fmt;
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use uuid;
 
 #[derive(Clone)]
@@ -15,6 +18,19 @@ pub struct Fragment {
     members_to_materialized: Vec<Member>,
     max_size_cache: usize,
     size: usize,
+    collection: Collection,
+    /// The number of members above which the fragment is split, [None] if it is never split
+    max_members: Option<usize>,
+    /// The date and the byte range in the file of the materialized members,
+    /// they are only tracked when the fragment can be split
+    member_locations: Vec<(i64, Range<usize>)>,
+    /// The size at which the next split is attempted after a split failed
+    /// because the members share the same date
+    next_split_size: usize,
+    /// The node holding the relations toward the fragment, [None] when it is the root node
+    parent: Option<PathBuf>,
+    /// The number of relations followed from the root node to reach the fragment
+    depth: usize,
 }
 
 impl Fragment {
@@ -23,9 +39,10 @@ impl Fragment {
         max_size_cache: usize,
         lower_bound: i64,
         upper_bound: i64,
-    ) -> Self {
-        fs::File::create(&filename).unwrap();
-        Self {
+        collection: Collection,
+    ) -> Result<Self, Error> {
+        fs::File::create(&filename).map_err(|e| Error::io(&filename, e))?;
+        Ok(Self {
             filename: filename.clone(),
             boundary: Boundary {
                 lower: lower_bound,
@@ -34,7 +51,25 @@ impl Fragment {
             members_to_materialized: Vec::with_capacity(max_size_cache),
             max_size_cache,
             size: 0,
-        }
+            collection,
+            max_members: None,
+            member_locations: Vec::new(),
+            next_split_size: 0,
+            parent: None,
+            depth: 1,
+        })
+    }
+
+    /// Split the fragment in two on the median date of its members when it holds
+    /// more than a maximum number of members
+    pub fn set_max_members(&mut self, max_members: Option<usize>) {
+        self.max_members = max_members;
+    }
+
+    /// The fragment holds more members than its maximum and should be split
+    pub fn exceeds_max_members(&self) -> bool {
+        self.max_members
+            .is_some_and(|max_members| self.size > max_members && self.size >= self.next_split_size)
     }
 
     pub fn boundary(&self) -> &Boundary {
@@ -42,46 +77,81 @@ impl Fragment {
     }
 
     pub fn up_boundary_infinity(&mut self) {
-        self.boundary.upper = chrono::NaiveDateTime::MAX.timestamp();
+        self.boundary.upper = i64::MAX;
     }
 
     pub fn size(&self) -> usize {
         self.size
     }
+    pub fn parent(&self) -> Option<&PathBuf> {
+        self.parent.as_ref()
+    }
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
     pub fn filename(&self) -> &PathBuf {
         &self.filename
     }
 
-    pub async fn materialize_relation(&self, relations: Vec<Relation>) {
+    pub async fn materialize_relation(&self, relations: Vec<Relation>) -> Result<(), Error> {
         let mut file = fs::OpenOptions::new()
             .append(true)
             .open(&self.filename)
-            .unwrap();
+            .map_err(|e| Error::io(&self.filename, e))?;
         for relation in relations {
             let buffer = relation.to_string();
-            file.write_all(buffer.as_bytes()).unwrap();
+            file.write_all(buffer.as_bytes())
+                .map_err(|e| Error::io(&self.filename, e))?;
         }
+        Ok(())
     }
-    pub async fn materialize(&mut self) {
+    pub async fn materialize(&mut self) -> Result<(), Error> {
         if self.members_to_materialized.len() > 0 {
-            let mut file = fs::OpenOptions::new()
-                .append(true)
-                .open(&self.filename)
-                .unwrap();
-            let buffer = {
-                let mut resp = String::new();
-                for member in self.members_to_materialized.iter() {
-                    resp.push_str(&member.to_string())
-                }
-                resp
-            };
-            file.write_all(buffer.as_bytes()).unwrap();
-            self.members_to_materialized = Vec::new();
+            let members = std::mem::take(&mut self.members_to_materialized);
+            let blocks: Vec<(i64, String)> = members
+                .iter()
+                .map(|member| {
+                    let mut resp = String::new();
+                    if !member.id.is_empty() {
+                        resp.push_str(&self.collection.member_to_string(&member.id));
+                    }
+                    resp.push_str(&member.to_string());
+                    (member.date, resp)
+                })
+                .collect();
+            self.write_member_blocks(blocks.iter().map(|(date, block)| (*date, block.as_bytes())))?;
         }
+        Ok(())
     }
 
-    pub fn clear_file(&self) {
-        fs::remove_file(&self.filename).expect("was not able to delete the fragment");
+    /// Append the serialized members to the file alongside their date
+    fn write_member_blocks<'a>(
+        &mut self,
+        blocks: impl Iterator<Item = (i64, &'a [u8])>,
+    ) -> Result<(), Error> {
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&self.filename)
+            .map_err(|e| Error::io(&self.filename, e))?;
+        let mut offset = file
+            .metadata()
+            .map_err(|e| Error::io(&self.filename, e))?
+            .len() as usize;
+        let mut buffer = Vec::new();
+        for (date, block) in blocks {
+            if self.max_members.is_some() {
+                self.member_locations
+                    .push((date, offset..offset + block.len()));
+            }
+            offset += block.len();
+            buffer.extend_from_slice(block);
+        }
+        file.write_all(&buffer)
+            .map_err(|e| Error::io(&self.filename, e))
+    }
+
+    pub fn clear_file(&self) -> Result<(), Error> {
+        fs::remove_file(&self.filename).map_err(|e| Error::io(&self.filename, e))
     }
 
     pub fn len(&self) -> usize {
@@ -89,7 +159,7 @@ impl Fragment {
     }
 
     pub fn insert(&mut self, member: &Member) -> Result<(), &str> {
-        if self.members_to_materialized.len() + 1 >= self.max_size_cache {
+        if self.members_to_materialized.len() >= self.max_size_cache {
             return Err("the member cache is full it has to be materialized");
         }
         self.size += 1;
@@ -97,89 +167,193 @@ impl Fragment {
         Ok(())
     }
 
-    pub async fn create_two_sub_fragment(
+    /// Split the fragment on the median date of its members, its members are moved to the
+    /// two sub fragments and the fragment only keeps its relations. Return [None] when
+    /// the members cannot be split because they share the same date.
+    pub async fn split_on_median_date(
         &mut self,
         fragmentation_property: &String,
         server_address: &String,
-    ) -> (Fragment, Fragment) {
-        self.materialize().await;
+    ) -> Result<Option<(Fragment, Fragment)>, Error> {
+        self.materialize().await?;
+        let mut dates: Vec<i64> = self
+            .member_locations
+            .iter()
+            .map(|(date, _)| *date)
+            .collect();
+        dates.sort_unstable();
+        // the members of the same date stay together, so the split date is above the lowest date
+        let median_date = dates[dates.len() / 2];
+        let split_date = if median_date > dates[0] {
+            median_date
+        } else if let Some(date) = dates.iter().find(|date| **date > median_date) {
+            *date
+        } else {
+            self.next_split_size = self.size * 2;
+            return Ok(None);
+        };
 
-        let mid_bound = self.boundary.lower + (self.boundary.upper - self.boundary.lower) / 2;
         let generate_filename = || {
             let mut resp = self.filename.clone();
             resp.pop();
-            resp.push(format!("{}.ttl", uuid::Uuid::new_v4().to_string()));
+            resp.push(self.collection.node_filename(uuid::Uuid::new_v4()));
             resp
         };
-
-        let fragment_1 = Fragment::new(
+        let mut fragment_1 = Fragment::new(
             generate_filename(),
             self.max_size_cache,
             self.boundary.lower,
-            mid_bound,
+            split_date,
+            self.collection.clone(),
         )
-        .await;
-
-        let fragment_2 = Fragment::new(
+        .await?;
+        let mut fragment_2 = Fragment::new(
             generate_filename(),
             self.max_size_cache,
-            mid_bound,
+            split_date,
             self.boundary.upper,
+            self.collection.clone(),
         )
-        .await;
-
-        let relations_1 = fragment_1.boundary.to_relation(
-            &self
-                .filename()
-                .as_path()
-                .file_name()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .to_string(),
-            &fragment_1
-                .filename()
-                .as_path()
-                .file_name()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .to_string(),
-            fragmentation_property,
-            server_address,
-        );
+        .await?;
+        fragment_1.set_max_members(self.max_members);
+        fragment_2.set_max_members(self.max_members);
+        fragment_1.parent = Some(self.filename.clone());
+        fragment_2.parent = Some(self.filename.clone());
+        fragment_1.depth = self.depth + 1;
+        fragment_2.depth = self.depth + 1;
+
+        let content = fs::read(&self.filename).map_err(|e| Error::io(&self.filename, e))?;
+        let member_locations = std::mem::take(&mut self.member_locations);
+        let mut remaining_content = Vec::with_capacity(content.len());
+        let mut cursor = 0;
+        for (_, range) in member_locations.iter() {
+            remaining_content.extend_from_slice(&content[cursor..range.start]);
+            cursor = range.end;
+        }
+        remaining_content.extend_from_slice(&content[cursor..]);
+        for (fragment, is_lower_fragment) in [(&mut fragment_1, true), (&mut fragment_2, false)] {
+            let blocks: Vec<(i64, &[u8])> = member_locations
+                .iter()
+                .filter(|(date, _)| (*date < split_date) == is_lower_fragment)
+                .map(|(date, range)| (*date, &content[range.clone()]))
+                .collect();
+            fragment.size = blocks.len();
+            fragment.write_member_blocks(blocks.into_iter())?;
+        }
+
+        // the fragment only keeps its relations, to which the relations to the sub fragments are added
+        fs::write(&self.filename, remaining_content).map_err(|e| Error::io(&self.filename, e))?;
+        self.size = 0;
+        let mut relations = Vec::new();
+        for fragment in [&fragment_1, &fragment_2] {
+            relations.append(&mut fragment.boundary.to_relation(
+                &self.node_name()?,
+                &fragment.node_name()?,
+                fragmentation_property,
+                server_address,
+            ));
+        }
+        self.materialize_relation(relations).await?;
 
-        let relations_2 = fragment_2.boundary.to_relation(
-            &self
-                .filename()
-                .as_path()
-                .file_name()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .to_string(),
-            &fragment_2
-                .filename()
-                .as_path()
-                .file_name()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .to_string(),
-            fragmentation_property,
-            server_address,
+        Ok(Some((fragment_1, fragment_2)))
+    }
+
+    /// Append the members of the adjacent fragment and widen the boundary to cover both fragments,
+    /// the file of the adjacent fragment is deleted. Both fragments have to be materialized.
+    pub fn merge(&mut self, fragment: Fragment) -> Result<(), Error> {
+        let content = fs::read(&fragment.filename).map_err(|e| Error::io(&fragment.filename, e))?;
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&self.filename)
+            .map_err(|e| Error::io(&self.filename, e))?;
+        let offset = file
+            .metadata()
+            .map_err(|e| Error::io(&self.filename, e))?
+            .len() as usize;
+        file.write_all(&content)
+            .map_err(|e| Error::io(&self.filename, e))?;
+        self.member_locations.extend(
+            fragment
+                .member_locations
+                .iter()
+                .map(|(date, range)| (*date, range.start + offset..range.end + offset)),
         );
+        self.size += fragment.size;
+        self.boundary.lower = self.boundary.lower.min(fragment.boundary.lower);
+        self.boundary.upper = self.boundary.upper.max(fragment.boundary.upper);
+        fragment.clear_file()
+    }
 
-        self.materialize_relation(relations_1).await;
-        self.materialize_relation(relations_2).await;
+    /// The filename of the fragment relative to the server address
+    pub fn node_name(&self) -> Result<String, Error> {
+        node_name(&self.filename)
+    }
 
-        (fragment_1, fragment_2)
+    /// Divide the boundary of the fragment in equal parts into sub fragments
+    /// that are linked from the fragment.
+    pub async fn create_sub_fragments(
+        &mut self,
+        n_sub_fragments: usize,
+        fragmentation_property: &String,
+        server_address: &String,
+    ) -> Result<Vec<Fragment>, Error> {
+        self.materialize().await?;
+
+        let bound = |i: usize| {
+            let span = self.boundary.upper as i128 - self.boundary.lower as i128;
+            (self.boundary.lower as i128 + span * i as i128 / n_sub_fragments as i128) as i64
+        };
+        let generate_filename = || {
+            let mut resp = self.filename.clone();
+            resp.pop();
+            resp.push(self.collection.node_filename(uuid::Uuid::new_v4()));
+            resp
+        };
+
+        let mut sub_fragments = Vec::with_capacity(n_sub_fragments);
+        for i in 0..n_sub_fragments {
+            let mut sub_fragment = Fragment::new(
+                generate_filename(),
+                self.max_size_cache,
+                bound(i),
+                bound(i + 1),
+                self.collection.clone(),
+            )
+            .await?;
+            sub_fragment.depth = self.depth + 1;
+            sub_fragments.push(sub_fragment);
+        }
+
+        let mut relations = Vec::new();
+        for sub_fragment in sub_fragments.iter() {
+            relations.append(&mut sub_fragment.boundary.to_relation(
+                &self.node_name()?,
+                &sub_fragment.node_name()?,
+                fragmentation_property,
+                server_address,
+            ));
+        }
+        self.materialize_relation(relations).await?;
+
+        Ok(sub_fragments)
     }
 }
 
+/// The filename of a node relative to the server address
+pub fn node_name(filename: &Path) -> Result<String, Error> {
+    filename
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.to_string())
+        .ok_or(Error::Fragmentation(format!(
+            "the node {} has no valid file name",
+            filename.display()
+        )))
+}
+
 impl fmt::Display for Fragment {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.filename.as_os_str().to_str().unwrap())
+        write!(f, "{}", self.filename.display())
     }
 }
 #[derive(Debug, Clone, serde::Serialize)]
@@ -190,16 +364,24 @@ pub struct Boundary {
 
 impl fmt::Display for Boundary {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let upper = chrono::NaiveDateTime::from_timestamp_opt(self.upper, 0)
-            .unwrap_or(chrono::NaiveDateTime::MAX);
-        let lower = chrono::NaiveDateTime::from_timestamp_opt(self.lower, 0)
-            .unwrap_or(chrono::NaiveDateTime::MIN);
+        let upper = if self.upper == i64::MAX {
+            chrono::NaiveDateTime::MAX
+        } else {
+            from_time_axis(self.upper)
+        };
+        let lower = if self.lower == i64::MIN {
+            chrono::NaiveDateTime::MIN
+        } else {
+            from_time_axis(self.lower)
+        };
         write!(f, "[{upper}, {lower}]")
     }
 }
 impl Boundary {
+    /// The boundary is half-open like the relations, the lower bound is included and the
+    /// upper bound is excluded unless it is open-ended.
     pub fn is_in_between(&self, date: i64) -> bool {
-        date >= self.lower && date <= self.upper
+        date >= self.lower && (date < self.upper || self.upper == i64::MAX)
     }
 
     pub fn to_relation(
@@ -210,7 +392,7 @@ impl Boundary {
         server_address: &String,
     ) -> Vec<Relation> {
         let mut resp: Vec<Relation> = Vec::new();
-        if self.upper < chrono::NaiveDateTime::MAX.timestamp() {
+        if self.upper < i64::MAX {
             resp.push(Self::create_relation(
                 self.upper,
                 fragmentation_property,
@@ -221,7 +403,7 @@ impl Boundary {
             ));
         }
 
-        if self.lower > chrono::NaiveDateTime::MIN.timestamp() {
+        if self.lower > i64::MIN {
             resp.push(Self::create_relation(
                 self.lower,
                 fragmentation_property,
@@ -246,8 +428,7 @@ impl Boundary {
         Relation::new(
             Some(fragmentation_property.clone()),
             Some(
-                chrono::NaiveDateTime::from_timestamp_opt(time_value, 0)
-                    .unwrap()
+                from_time_axis(time_value)
                     .format(DATE_TIME_FORMAT)
                     .to_string(),
             ),
@@ -268,4 +449,5 @@ impl Default for Boundary {
     }
 }
 
-const DATE_TIME_FORMAT: &'static str = "%Y-%m-%dT%H:%M:%S.%f";
+/// The time axis is in UTC, the `tree:value`s carry the time zone so that they can be compared with the member dates
+const DATE_TIME_FORMAT: &'static str = "%Y-%m-%dT%H:%M:%S%.fZ";
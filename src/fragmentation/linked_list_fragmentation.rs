@@ -1,7 +1,8 @@
 use super::fragment::*;
 use super::one_ary_tree_fragmentation::*;
+use crate::error::Error;
 use crate::member::Member;
-use crate::tree::Relation;
+use crate::tree::{Collection, Relation};
 use async_trait;
 use futures;
 use futures::stream::StreamExt;
@@ -16,34 +17,34 @@ pub struct LinkedListFragmentation {
 
 impl LinkedListFragmentation {
     pub async fn new(
-        n_fragments: usize,
-        max_size_cache: usize,
         folder: &PathBuf,
         highest_date: i64,
         lowest_date: i64,
         server_address: String,
         fragmentation_property: String,
-    ) -> Self {
+        options: &super::FragmentationOptions,
+        collection: Collection,
+    ) -> Result<Self, Error> {
         let one_ary_tree_fragmentation = OneAryTreeFragmentation::new(
-            n_fragments,
-            max_size_cache,
             folder,
             highest_date,
             lowest_date,
             server_address,
             fragmentation_property,
+            options,
+            collection,
         )
-        .await;
+        .await?;
 
-        Self {
+        Ok(Self {
             one_ary_tree_fragmentation,
-        }
+        })
     }
 
-    fn generate_root_node(&self) {
+    fn generate_root_node(&self) -> Result<(), Error> {
         let filename = {
             let mut resp = self.one_ary_tree_fragmentation.folder.clone();
-            resp.push(format!("0.ttl"));
+            resp.push(self.one_ary_tree_fragmentation.collection.node_filename(0));
             resp
         };
 
@@ -51,70 +52,85 @@ impl LinkedListFragmentation {
             .write(true)
             .truncate(true)
             .create(true)
-            .open(filename)
-            .unwrap();
-        let relation = Relation::new_unconstraint(
-            format!("{}1.ttl", self.one_ary_tree_fragmentation.server_address),
-            format!("{}0.ttl", self.one_ary_tree_fragmentation.server_address),
-            uuid::Uuid::new_v4().to_string(),
-        );
+            .open(&filename)
+            .map_err(|e| Error::io(&filename, e))?;
+        let collection = &self.one_ary_tree_fragmentation.collection;
+        // the first fragment can have been split or deleted by the rebalance,
+        // the list is empty when no member was inserted
+        let relations = self
+            .one_ary_tree_fragmentation
+            .fragments
+            .first()
+            .map(|fragment| {
+                Ok(Relation::new_unconstraint(
+                    format!(
+                        "{}{}",
+                        self.one_ary_tree_fragmentation.server_address,
+                        fragment.node_name()?
+                    ),
+                    format!(
+                        "{}{}",
+                        self.one_ary_tree_fragmentation.server_address,
+                        collection.node_filename(0)
+                    ),
+                    uuid::Uuid::new_v4().to_string(),
+                ))
+            })
+            .into_iter()
+            .collect::<Result<Vec<Relation>, Error>>()?;
 
-        let buffer = super::relations_to_string(vec![relation]);
-        file.write_all(buffer.as_bytes()).unwrap();
+        let mut buffer = self.one_ary_tree_fragmentation.collection.to_string();
+        buffer.push_str(&super::relations_to_string(relations));
+        file.write_all(buffer.as_bytes())
+            .map_err(|e| Error::io(&filename, e))
+    }
+    /// The split fragments are not part of the list, their halves are linked in their place
+    fn delete_split_nodes(&self) -> Result<(), Error> {
+        for split_node in self.one_ary_tree_fragmentation.split_nodes.iter() {
+            fs::remove_file(split_node).map_err(|e| Error::io(split_node, e))?;
+        }
+        Ok(())
     }
     fn set_up_boundary_to_infinity(&mut self) {
         for fragment in self.one_ary_tree_fragmentation.fragments.iter_mut() {
             fragment.up_boundary_infinity();
         }
     }
-    async fn add_relation_to_nodes(&self) {
+    async fn add_relation_to_nodes(&self) -> Result<(), Error> {
         let tasks = futures_util::stream::FuturesUnordered::new();
-        for i in 0..self.one_ary_tree_fragmentation.n_fragments - 1 {
-            let fragment_1 = &self.one_ary_tree_fragmentation.fragments[i];
-            let fragment_2 = &self.one_ary_tree_fragmentation.fragments[i + 1];
+        for pair in self.one_ary_tree_fragmentation.fragments.windows(2) {
+            let (fragment_1, fragment_2) = (&pair[0], &pair[1]);
             let relations = fragment_2.boundary().to_relation(
-                &fragment_1
-                    .filename()
-                    .as_path()
-                    .file_name()
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .to_string(),
-                &fragment_2
-                    .filename()
-                    .as_path()
-                    .file_name()
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .to_string(),
+                &fragment_1.node_name()?,
+                &fragment_2.node_name()?,
                 &self.one_ary_tree_fragmentation.fragmentation_property,
                 &self.one_ary_tree_fragmentation.server_address,
             );
             tasks.push(fragment_1.materialize_relation(relations));
         }
-        let _: Vec<_> = tasks.collect().await;
+        let results: Vec<Result<(), Error>> = tasks.collect().await;
+        results.into_iter().collect()
     }
 }
 
 #[async_trait::async_trait]
 impl super::Fragmentation for LinkedListFragmentation {
-    async fn insert(&mut self, member: &Member) {
-        self.one_ary_tree_fragmentation.insert(member).await;
+    async fn insert(&mut self, member: &Member) -> Result<(), Error> {
+        self.one_ary_tree_fragmentation.insert(member).await
     }
 
-    async fn finalize(&mut self) {
+    async fn finalize(&mut self) -> Result<(), Error> {
         self.set_up_boundary_to_infinity();
-        self.one_ary_tree_fragmentation.materialize().await;
-        self.one_ary_tree_fragmentation.rebalance().await;
-        self.generate_root_node();
-        self.add_relation_to_nodes().await;
+        self.one_ary_tree_fragmentation.materialize().await?;
+        self.one_ary_tree_fragmentation.rebalance().await?;
+        self.delete_split_nodes()?;
+        self.generate_root_node()?;
+        self.add_relation_to_nodes().await?;
         self.print_summary();
         super::create_report(
             &self.one_ary_tree_fragmentation.fragments,
             &self.one_ary_tree_fragmentation.folder,
-        );
+        )
     }
 
     fn max_size_cache(&self) -> usize {
@@ -125,3 +141,51 @@ impl super::Fragmentation for LinkedListFragmentation {
         &self.one_ary_tree_fragmentation.fragments
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fragmentation::tree::PlacementPolicy;
+    use crate::fragmentation::{Fragmentation, FragmentationOptions, FragmentationTypeName};
+    use crate::tree::OutputFormat;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn finalize_an_empty_data_dump() {
+        let folder = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        fs::create_dir_all(&folder).unwrap();
+        let server_address = "http://localhost:8080/".to_string();
+        let options = FragmentationOptions {
+            fragmentation_type: FragmentationTypeName::LinkedList,
+            n_fragments_first_row: 3,
+            max_size_cache: 1,
+            dept: None,
+            branching_factor: 2,
+            placement_policy: PlacementPolicy::Random,
+            seed: None,
+            b_plus_tree_parameters: None,
+            max_members_per_fragment: None,
+            fill_factor: 0.1,
+        };
+        let mut fragmentation = LinkedListFragmentation::new(
+            &folder,
+            1_000,
+            0,
+            server_address.clone(),
+            "http://ex.org/date".to_string(),
+            &options,
+            Collection::from_server_address(&server_address, OutputFormat::Turtle),
+        )
+        .await
+        .unwrap();
+
+        fragmentation.finalize().await.unwrap();
+
+        // the empty fragments are deleted, the root node is left without relation
+        assert!(fragmentation.fragments().is_empty());
+        let root_node = fs::read_to_string(folder.join("0.ttl")).unwrap();
+        assert!(!root_node.contains("https://w3id.org/tree#relation"));
+        let report = fs::read_to_string(folder.join("report.json")).unwrap();
+        assert_eq!(report, "{}");
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
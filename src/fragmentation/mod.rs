@@ -1,26 +1,29 @@
+pub mod b_plus_tree_fragmentation;
 pub mod fragment;
 pub mod linked_list_fragmentation;
 pub mod one_ary_tree_fragmentation;
-mod report;
+pub mod report;
 pub mod tree;
 
+use self::b_plus_tree_fragmentation::{BPlusTreeFragmentation, BPlusTreeParameters};
 use self::fragment::*;
 use self::linked_list_fragmentation::LinkedListFragmentation;
 use self::one_ary_tree_fragmentation::OneAryTreeFragmentation;
-use self::report::Report;
-use self::tree::Tree;
+use self::report::{DataQualityReport, Report};
+use self::tree::{PlacementPolicy, Tree};
+use crate::error::Error;
 use crate::member::Member;
 use crate::tree::*;
-use serde_json;
+use futures::stream::StreamExt;
 use std::collections::HashMap;
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[async_trait::async_trait]
 pub trait Fragmentation {
-    async fn insert(&mut self, member: &Member);
-    async fn finalize(&mut self);
+    async fn insert(&mut self, member: &Member) -> Result<(), Error>;
+    async fn finalize(&mut self) -> Result<(), Error>;
     fn max_size_cache(&self) -> usize;
     fn fragments(&self) -> &Vec<Fragment>;
     fn print_summary(&self) {
@@ -55,10 +58,11 @@ pub(super) fn generate_central_root_node(
     fragments: &Vec<Fragment>,
     fragmentation_property: &String,
     server_address: &String,
-) {
+    collection: &Collection,
+) -> Result<(), Error> {
     let filename = {
         let mut resp = folder.clone();
-        resp.push(format!("0.ttl"));
+        resp.push(collection.node_filename(0));
         resp
     };
 
@@ -66,31 +70,35 @@ pub(super) fn generate_central_root_node(
         .write(true)
         .truncate(true)
         .create(true)
-        .open(filename)
-        .unwrap();
+        .open(&filename)
+        .map_err(|e| Error::io(&filename, e))?;
     let mut relations: Vec<Relation> = Vec::with_capacity(n_fragments);
     for fragment in fragments.iter() {
-        relations.append(
-            &mut fragment.boundary().to_relation(
-                &"0.ttl".to_string(),
-                &fragment
-                    .filename()
-                    .as_path()
-                    .file_name()
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .to_string(),
-                fragmentation_property,
-                server_address,
-            ),
-        );
+        relations.append(&mut fragment.boundary().to_relation(
+            &collection.node_filename(0),
+            &fragment.node_name()?,
+            fragmentation_property,
+            server_address,
+        ));
+    }
+    let mut buffer = collection.to_string();
+    buffer.push_str(&relations_to_string(relations));
+    file.write_all(buffer.as_bytes())
+        .map_err(|e| Error::io(&filename, e))
+}
+
+/// Write the members cached by the fragments
+pub(super) async fn materialize_fragments(fragments: &mut [Fragment]) -> Result<(), Error> {
+    let materialize_tasks = futures_util::stream::FuturesUnordered::new();
+    for fragment in fragments.iter_mut() {
+        materialize_tasks.push(fragment.materialize());
     }
-    let buffer = relations_to_string(relations);
-    file.write_all(buffer.as_bytes()).unwrap();
+
+    let results: Vec<Result<(), Error>> = materialize_tasks.collect().await;
+    results.into_iter().collect()
 }
 
-pub(super) fn create_report(fragments: &Vec<Fragment>, folder: &PathBuf) {
+pub(super) fn create_report(fragments: &Vec<Fragment>, folder: &PathBuf) -> Result<(), Error> {
     let mut map_report = HashMap::new();
     for fragment in fragments.iter() {
         let report = Report {
@@ -102,86 +110,158 @@ pub(super) fn create_report(fragments: &Vec<Fragment>, folder: &PathBuf) {
             boundary: fragment.boundary().clone(),
         };
         map_report.insert(fragment.filename().clone(), report);
-        let json_string = serde_json::to_string(&map_report).expect("unable to produce the report");
+    }
+    // the report is written even without fragment so that the one of a previous step is replaced
+    let json_string = serde_json::to_string(&map_report)
+        .map_err(|e| Error::Fragmentation(format!("unable to produce the report: {e}")))?;
 
-        let report_path = {
-            let mut resp = folder.clone();
-            resp.push("report.json");
-            resp
-        };
+    let report_path = {
+        let mut resp = folder.clone();
+        resp.push("report.json");
+        resp
+    };
+
+    fs::write(&report_path, json_string).map_err(|e| Error::io(&report_path, e))
+}
+pub fn create_data_quality_report(report: &DataQualityReport, folder: &Path) -> Result<(), Error> {
+    let json_string = serde_json::to_string(report).map_err(|e| {
+        Error::Fragmentation(format!("unable to produce the data quality report: {e}"))
+    })?;
+    let report_path = folder.join("data_quality.json");
+
+    fs::write(&report_path, json_string).map_err(|e| Error::io(&report_path, e))
+}
 
-        fs::write(report_path, json_string).expect("unable to generate the report file");
+/// Split the fragment at the position when it holds more members than its maximum,
+/// it is replaced by its two sub fragments so that the order of the fragments is kept.
+/// The split fragment, which only holds the relations toward its sub fragments, is returned.
+pub(super) async fn split_full_fragment(
+    fragments: &mut Vec<Fragment>,
+    pos: usize,
+    fragmentation_property: &String,
+    server_address: &String,
+) -> Result<Option<Fragment>, Error> {
+    if fragments[pos].exceeds_max_members() {
+        if let Some((fragment_1, fragment_2)) = fragments[pos]
+            .split_on_median_date(fragmentation_property, server_address)
+            .await?
+        {
+            return Ok(fragments.splice(pos..=pos, [fragment_1, fragment_2]).next());
+        }
     }
+    Ok(None)
+}
+
+/// The width of the fragments dividing the time axis between the lowest and the highest date in equal parts,
+/// it is computed on integers so that the precision of the time axis is kept
+fn increment(highest_date: i64, lowest_date: i64, n_fragments: usize) -> i64 {
+    let span = (highest_date as i128 - lowest_date as i128).max(0);
+    let n_fragments = n_fragments as i128;
+    ((span + n_fragments - 1) / n_fragments) as i64
 }
+
 #[derive(Clone, Debug)]
 pub enum FragmentationTypeName {
     OneAryTree,
     LinkedList,
     Tree,
+    BPlusTree,
 }
 
-impl From<String> for FragmentationTypeName {
-    fn from(item: String) -> Self {
+impl TryFrom<String> for FragmentationTypeName {
+    type Error = Error;
+
+    fn try_from(item: String) -> Result<Self, Self::Error> {
         if item == "oneAryTree".to_string() {
-            Self::OneAryTree
+            Ok(Self::OneAryTree)
         } else if item == "linkedList".to_string() {
-            Self::LinkedList
+            Ok(Self::LinkedList)
         } else if item == "tree".to_string() {
-            Self::Tree
+            Ok(Self::Tree)
+        } else if item == "bPlusTree" {
+            Ok(Self::BPlusTree)
         } else {
-            panic!("fragmentation {} not supported", item)
+            Err(Error::Config(format!("fragmentation {item} not supported")))
         }
     }
 }
 
+/// The options of the fragmentation, each fragmentation only uses the ones it needs
+#[derive(Clone, Debug)]
+pub struct FragmentationOptions {
+    pub fragmentation_type: FragmentationTypeName,
+    pub n_fragments_first_row: usize,
+    /// The number of members kept in memory by fragment before they are materialized
+    pub max_size_cache: usize,
+    pub dept: Option<usize>,
+    pub branching_factor: usize,
+    pub placement_policy: PlacementPolicy,
+    pub seed: Option<u64>,
+    pub b_plus_tree_parameters: Option<BPlusTreeParameters>,
+    pub max_members_per_fragment: Option<usize>,
+    pub fill_factor: f64,
+}
+
 pub async fn factory(
-    fragmentation_type: FragmentationTypeName,
-    n_fragments_first_row: usize,
-    max_size_cache: usize,
+    options: FragmentationOptions,
     folder: &PathBuf,
     highest_date: i64,
     lowest_date: i64,
     server_address: String,
     fragmentation_property: String,
-    dept: Option<usize>,
-) -> Box<dyn Fragmentation> {
-    match fragmentation_type {
+    collection: Collection,
+) -> Result<Box<dyn Fragmentation>, Error> {
+    Ok(match options.fragmentation_type {
         FragmentationTypeName::LinkedList => Box::new(
             LinkedListFragmentation::new(
-                n_fragments_first_row,
-                max_size_cache,
                 &folder,
                 highest_date,
                 lowest_date,
                 server_address,
                 fragmentation_property,
+                &options,
+                collection,
             )
-            .await,
+            .await?,
         ),
         FragmentationTypeName::OneAryTree => Box::new(
             OneAryTreeFragmentation::new(
-                n_fragments_first_row,
-                max_size_cache,
                 &folder,
                 highest_date,
                 lowest_date,
                 server_address,
                 fragmentation_property,
+                &options,
+                collection,
             )
-            .await,
+            .await?,
         ),
         FragmentationTypeName::Tree => Box::new(
             Tree::new(
-                n_fragments_first_row,
-                max_size_cache,
                 &folder,
                 highest_date,
                 lowest_date,
                 server_address,
                 fragmentation_property,
-                dept.expect("the dept should be defined to create a tree"),
+                &options,
+                collection,
             )
-            .await,
+            .await?,
         ),
-    }
+        FragmentationTypeName::BPlusTree => Box::new(
+            BPlusTreeFragmentation::new(
+                options.max_size_cache,
+                folder,
+                server_address,
+                fragmentation_property,
+                options.b_plus_tree_parameters.ok_or(Error::Config(
+                    "the distribution of the dates found with --pre-scan is needed to create a B+tree"
+                        .to_string(),
+                ))?,
+                options.max_members_per_fragment,
+                collection,
+            )
+            .await?,
+        ),
+    })
 }
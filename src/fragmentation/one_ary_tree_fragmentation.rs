@@ -1,9 +1,13 @@
 use super::fragment::*;
+use crate::error::Error;
 use crate::member::Member;
+use crate::tree::{Collection, Relation};
 use async_trait;
-use chrono;
 use futures;
 use futures::stream::StreamExt;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Write;
 use std::path::PathBuf;
 
 pub struct OneAryTreeFragmentation {
@@ -13,28 +17,34 @@ pub struct OneAryTreeFragmentation {
     pub(super) folder: PathBuf,
     pub(super) server_address: String,
     pub(super) fragmentation_property: String,
+    pub(super) collection: Collection,
+    /// The fraction of the average number of members below which a fragment is merged with its neighbour
+    pub(super) fill_factor: f64,
+    /// The files of the fragments split during the ingestion, they only hold relations
+    pub(super) split_nodes: Vec<PathBuf>,
 }
 
 impl OneAryTreeFragmentation {
     pub async fn new(
-        n_fragments: usize,
-        max_size_cache: usize,
         folder: &PathBuf,
         highest_date: i64,
         lowest_date: i64,
         server_address: String,
         fragmentation_property: String,
-    ) -> Self {
+        options: &super::FragmentationOptions,
+        collection: Collection,
+    ) -> Result<Self, Error> {
+        let n_fragments = options.n_fragments_first_row;
+        let max_size_cache = options.max_size_cache;
         let fragments = {
             let tasks = futures_util::stream::FuturesUnordered::new();
             let mut current_lower_bound = lowest_date;
 
-            let increment =
-                ((highest_date as f32 - lowest_date as f32) / n_fragments as f32).ceil() as i64;
+            let increment = super::increment(highest_date, lowest_date, n_fragments);
             for i in 0..n_fragments {
                 let fragment_path = {
                     let mut resp = folder.clone();
-                    resp.push(format!("{}.ttl", i + 1));
+                    resp.push(collection.node_filename(i + 1));
                     resp
                 };
 
@@ -42,19 +52,24 @@ impl OneAryTreeFragmentation {
                     fragment_path,
                     max_size_cache,
                     if i == 0 {
-                        chrono::NaiveDateTime::MIN.timestamp()
+                        i64::MIN
                     } else {
                         current_lower_bound
                     },
                     if i == n_fragments - 1 {
-                        chrono::NaiveDateTime::MAX.timestamp()
+                        i64::MAX
                     } else {
                         current_lower_bound + increment
                     },
+                    collection.clone(),
                 ));
                 current_lower_bound += increment;
             }
-            let resp: Vec<Fragment> = tasks.collect().await;
+            let resp: Vec<Result<Fragment, Error>> = tasks.collect().await;
+            let mut resp = resp.into_iter().collect::<Result<Vec<Fragment>, Error>>()?;
+            for fragment in resp.iter_mut() {
+                fragment.set_max_members(options.max_members_per_fragment);
+            }
             resp
         };
 
@@ -64,47 +79,131 @@ impl OneAryTreeFragmentation {
             &fragments,
             &fragmentation_property,
             &server_address,
-        );
+            &collection,
+        )?;
 
-        super::create_report(&fragments, &folder);
+        super::create_report(&fragments, &folder)?;
 
-        Self {
+        Ok(Self {
             fragments,
             n_fragments,
             max_size_cache,
             folder: folder.clone(),
             server_address,
             fragmentation_property,
-        }
+            collection,
+            fill_factor: options.fill_factor,
+            split_nodes: Vec::new(),
+        })
     }
 
-    /// It simply delete the fragment with a size of 0, and merge two adjacent fragment
-    /// if the current fragment has 10 times less members than the average.
-    pub(super) async fn rebalance(&mut self) {
-        self.fragments.retain(|fragment| {
+    /// It deletes the fragments with a size of 0 and merges the adjacent fragments having less members
+    /// than the fill factor times the average. Only the fragments linked from the same node are merged,
+    /// so the relations of this node are rewritten toward the merged fragment.
+    pub(super) async fn rebalance(&mut self) -> Result<(), Error> {
+        // the nodes whose relations are outdated grouped by the node holding the relations
+        let mut outdated_nodes: HashMap<Option<PathBuf>, Vec<String>> = HashMap::new();
+        let mut merged_fragments: HashSet<PathBuf> = HashSet::new();
+        for fragment in self.fragments.iter() {
             if fragment.size() == 0 {
-                fragment.clear_file();
-                false
-            } else {
-                true
+                fragment.clear_file()?;
+                outdated_nodes
+                    .entry(fragment.parent().cloned())
+                    .or_default()
+                    .push(fragment.node_name()?);
+            }
+        }
+        self.fragments.retain(|fragment| fragment.size() != 0);
+
+        let n_members: usize = self.fragments.iter().map(|fragment| fragment.size()).sum();
+        let min_size = self.fill_factor * n_members as f64 / self.fragments.len().max(1) as f64;
+        let mut i = 0;
+        while i < self.fragments.len() {
+            if self.fragments[i].size() as f64 >= min_size {
+                i += 1;
+                continue;
             }
-        });
+            let is_sibling = |j: usize| {
+                self.fragments
+                    .get(j)
+                    .is_some_and(|fragment| fragment.parent() == self.fragments[i].parent())
+            };
+            let previous = i.checked_sub(1).filter(|j| is_sibling(*j));
+            let next = Some(i + 1).filter(|j| is_sibling(*j));
+            // the fragment is merged with its smallest neighbour
+            let neighbour = match (previous, next) {
+                (Some(previous), Some(next)) => {
+                    if self.fragments[previous].size() <= self.fragments[next].size() {
+                        previous
+                    } else {
+                        next
+                    }
+                }
+                (Some(neighbour), None) | (None, Some(neighbour)) => neighbour,
+                (None, None) => {
+                    i += 1;
+                    continue;
+                }
+            };
+            let (lower, upper) = (i.min(neighbour), i.max(neighbour));
+            let fragment = self.fragments.remove(upper);
+            let nodes = outdated_nodes
+                .entry(fragment.parent().cloned())
+                .or_default();
+            nodes.push(self.fragments[lower].node_name()?);
+            nodes.push(fragment.node_name()?);
+            merged_fragments.remove(fragment.filename());
+            self.fragments[lower].merge(fragment)?;
+            merged_fragments.insert(self.fragments[lower].filename().clone());
+            i = lower;
+        }
         self.n_fragments = self.fragments.len();
-    }
 
-    pub(super) async fn materialize(&mut self) {
-        let materialize_tasks = futures_util::stream::FuturesUnordered::new();
-        for fragment in self.fragments.iter_mut() {
-            materialize_tasks.push(fragment.materialize());
+        let root_path = {
+            let mut resp = self.folder.clone();
+            resp.push(self.collection.node_filename(0));
+            resp
+        };
+        for (parent, nodes) in outdated_nodes.iter() {
+            let parent_path = parent.as_ref().unwrap_or(&root_path);
+            let node_iris: Vec<String> = nodes
+                .iter()
+                .map(|node| format!("{}{node}", self.server_address))
+                .collect();
+            let content = fs::read_to_string(parent_path).map_err(|e| Error::io(parent_path, e))?;
+            fs::write(parent_path, Relation::remove_from(&content, &node_iris))
+                .map_err(|e| Error::io(parent_path, e))?;
         }
+        for fragment in self
+            .fragments
+            .iter()
+            .filter(|fragment| merged_fragments.contains(fragment.filename()))
+        {
+            let parent_path = fragment.parent().unwrap_or(&root_path);
+            let relations = fragment.boundary().to_relation(
+                &node_name(parent_path)?,
+                &fragment.node_name()?,
+                &self.fragmentation_property,
+                &self.server_address,
+            );
+            let mut file = fs::OpenOptions::new()
+                .append(true)
+                .open(parent_path)
+                .map_err(|e| Error::io(parent_path, e))?;
+            file.write_all(super::relations_to_string(relations).as_bytes())
+                .map_err(|e| Error::io(parent_path, e))?;
+        }
+        Ok(())
+    }
 
-        let _: Vec<_> = materialize_tasks.collect().await;
+    pub(super) async fn materialize(&mut self) -> Result<(), Error> {
+        super::materialize_fragments(&mut self.fragments).await
     }
 }
 
 #[async_trait::async_trait]
 impl super::Fragmentation for OneAryTreeFragmentation {
-    async fn insert(&mut self, member: &Member) {
+    async fn insert(&mut self, member: &Member) -> Result<(), Error> {
         let mut pos = 0;
         for (i, fragment) in self.fragments.iter().enumerate() {
             if fragment.boundary().is_in_between(member.date) {
@@ -112,16 +211,30 @@ impl super::Fragmentation for OneAryTreeFragmentation {
                 break;
             }
         }
-        if let Err(_) = self.fragments[pos].insert(&member) {
-            self.materialize().await;
-            self.fragments[pos].insert(&member).unwrap();
+        if self.fragments[pos].insert(member).is_err() {
+            self.materialize().await?;
+            self.fragments[pos]
+                .insert(member)
+                .map_err(|e| Error::Fragmentation(e.to_string()))?;
         }
+        if let Some(split_node) = super::split_full_fragment(
+            &mut self.fragments,
+            pos,
+            &self.fragmentation_property,
+            &self.server_address,
+        )
+        .await?
+        {
+            self.split_nodes.push(split_node.filename().clone());
+        }
+        self.n_fragments = self.fragments.len();
+        Ok(())
     }
-    async fn finalize(&mut self) {
-        self.materialize().await;
-        self.rebalance().await;
+    async fn finalize(&mut self) -> Result<(), Error> {
+        self.materialize().await?;
+        self.rebalance().await?;
         self.print_summary();
-        super::create_report(&self.fragments, &self.folder);
+        super::create_report(&self.fragments, &self.folder)
     }
 
     fn fragments(&self) -> &Vec<Fragment> {
@@ -132,3 +245,85 @@ impl super::Fragmentation for OneAryTreeFragmentation {
         self.max_size_cache
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fragmentation::tree::{PlacementPolicy, Tree};
+    use crate::fragmentation::{Fragmentation, FragmentationOptions, FragmentationTypeName};
+    use crate::tree::OutputFormat;
+
+    fn options(fragmentation_type: FragmentationTypeName) -> FragmentationOptions {
+        FragmentationOptions {
+            fragmentation_type,
+            n_fragments_first_row: 2,
+            max_size_cache: 10,
+            dept: Some(1),
+            branching_factor: 2,
+            placement_policy: PlacementPolicy::LeafOnly,
+            seed: None,
+            b_plus_tree_parameters: None,
+            max_members_per_fragment: None,
+            fill_factor: 0.1,
+        }
+    }
+
+    fn member(date: i64) -> Member {
+        Member {
+            id: "<http://ex.org/obs/1>".to_string(),
+            properties: vec!["<http://ex.org/obs/1> <http://ex.org/p> \"a\" .".to_string()],
+            date,
+            date_priority: Some(0),
+            ..Default::default()
+        }
+    }
+
+    /// The lower bound of the only fragment holding a member
+    fn lower_bound_of_the_member(fragments: &[Fragment]) -> i64 {
+        let fragments: Vec<&Fragment> = fragments
+            .iter()
+            .filter(|fragment| fragment.size() == 1)
+            .collect();
+        assert_eq!(fragments.len(), 1);
+        fragments[0].boundary().lower
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_member_on_a_shared_bound_goes_to_the_upper_fragment() {
+        let folder = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        fs::create_dir_all(&folder).unwrap();
+        let server_address = "http://localhost:8080/".to_string();
+        let collection = Collection::from_server_address(&server_address, OutputFormat::Turtle);
+
+        let mut one_ary_tree = OneAryTreeFragmentation::new(
+            &folder,
+            1_000,
+            0,
+            server_address.clone(),
+            "http://ex.org/date".to_string(),
+            &options(FragmentationTypeName::OneAryTree),
+            collection.clone(),
+        )
+        .await
+        .unwrap();
+        one_ary_tree.insert(&member(500)).await.unwrap();
+        // the fragments are [MIN, 500[ and [500, MAX[
+        assert_eq!(lower_bound_of_the_member(one_ary_tree.fragments()), 500);
+
+        let mut tree = Tree::new(
+            &folder,
+            1_000,
+            0,
+            server_address,
+            "http://ex.org/date".to_string(),
+            &options(FragmentationTypeName::Tree),
+            collection,
+        )
+        .await
+        .unwrap();
+        tree.insert(&member(500)).await.unwrap();
+        // the leaves are [-500, 0[, [0, 500[, [500, 1000[ and [1000, 1500[
+        assert_eq!(lower_bound_of_the_member(tree.fragments()), 500);
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -1,8 +1,42 @@
-use serde;
 use super::fragment::Boundary;
+use serde;
+use std::collections::HashMap;
 
 #[derive(serde::Serialize)]
-pub struct Report{
+pub struct Report {
     pub n_member: Option<usize>,
-    pub boundary: Boundary
-}
\ No newline at end of file
+    pub boundary: Boundary,
+}
+
+/// The quality of the members of the data dump
+#[derive(serde::Serialize, Default, Debug, Clone)]
+pub struct DataQualityReport {
+    pub n_valid_members: usize,
+    pub n_invalid_members: usize,
+    /// The number of invalid members by violation
+    pub violations: HashMap<String, usize>,
+}
+
+impl DataQualityReport {
+    pub fn add_valid_member(&mut self) {
+        self.n_valid_members += 1;
+    }
+
+    pub fn add_invalid_member(&mut self, violations: &Vec<String>) {
+        self.n_invalid_members += 1;
+        for violation in violations {
+            *self.violations.entry(violation.clone()).or_default() += 1;
+        }
+    }
+
+    /// Count as invalid the members of the report that were counted as valid before being rejected
+    pub fn reject_valid_members(&mut self, rejected: &DataQualityReport) {
+        self.n_valid_members = self
+            .n_valid_members
+            .saturating_sub(rejected.n_invalid_members);
+        self.n_invalid_members += rejected.n_invalid_members;
+        for (violation, n) in rejected.violations.iter() {
+            *self.violations.entry(violation.clone()).or_default() += n;
+        }
+    }
+}
@@ -1,38 +1,78 @@
 use super::Fragment;
+use crate::error::Error;
 use crate::member::Member;
+use crate::tree::Collection;
 use futures;
 use futures::stream::StreamExt;
 use rand::{self, Rng, SeedableRng};
 use std::path::PathBuf;
 
+/// How a member is placed among the fragments of the tree covering its date
+#[derive(Clone, Debug)]
+pub enum PlacementPolicy {
+    /// In one of the fragments chosen randomly
+    Random,
+    /// In the leaf covering the date, the inner nodes only hold relations
+    LeafOnly,
+    /// In the deepest fragment covering the date
+    DeepestMatching,
+}
+
+impl TryFrom<String> for PlacementPolicy {
+    type Error = Error;
+
+    fn try_from(item: String) -> Result<Self, Self::Error> {
+        if item == "random" {
+            Ok(Self::Random)
+        } else if item == "leafOnly" {
+            Ok(Self::LeafOnly)
+        } else if item == "deepestMatching" {
+            Ok(Self::DeepestMatching)
+        } else {
+            Err(Error::Config(format!(
+                "placement policy {item} not supported"
+            )))
+        }
+    }
+}
+
 pub struct Tree {
     fragments: Vec<Fragment>,
     max_size_cache: usize,
+    placement_policy: PlacementPolicy,
+    /// The depth of the leaves created with the tree, the fragments split afterward are deeper
+    leaf_depth: usize,
     random_generator: rand::rngs::StdRng,
     folder: PathBuf,
+    server_address: String,
+    fragmentation_property: String,
 }
 
 impl Tree {
     pub async fn new(
-        n_fragments_first_row: usize,
-        max_size_cache: usize,
         folder: &PathBuf,
         highest_date: i64,
         lowest_date: i64,
         server_address: String,
         fragmentation_property: String,
-        dept: usize,
-    ) -> Self {
+        options: &super::FragmentationOptions,
+        collection: Collection,
+    ) -> Result<Self, Error> {
+        let n_fragments_first_row = options.n_fragments_first_row;
+        let max_size_cache = options.max_size_cache;
+        let branching_factor = options.branching_factor;
+        let dept = options.dept.ok_or(Error::Config(
+            "the dept should be defined to create a tree".to_string(),
+        ))?;
         let fragments = {
             let tasks_create_first_row = futures_util::stream::FuturesUnordered::new();
             let mut current_lower_bound = lowest_date;
 
-            let increment =
-                ((highest_date as f32 - lowest_date as f32) / n_fragments_first_row as f32).ceil() as i64;
+            let increment = super::increment(highest_date, lowest_date, n_fragments_first_row);
             for i in 0..n_fragments_first_row {
                 let fragment_path = {
                     let mut resp = folder.clone();
-                    resp.push(format!("{}.ttl", i + 1));
+                    resp.push(collection.node_filename(i + 1));
                     resp
                 };
 
@@ -40,83 +80,124 @@ impl Tree {
                     fragment_path,
                     max_size_cache,
                     if i == 0 {
-                        current_lower_bound - increment
+                        current_lower_bound.saturating_sub(increment)
                     } else {
                         current_lower_bound
                     },
                     if i == n_fragments_first_row - 1 {
-                        current_lower_bound + 2 * increment
+                        current_lower_bound.saturating_add(increment.saturating_mul(2))
                     } else {
                         current_lower_bound + increment
                     },
+                    collection.clone(),
                 ));
                 current_lower_bound += increment;
             }
-            let mut resp: Vec<Fragment> = tasks_create_first_row.collect().await;
+            let resp: Vec<Result<Fragment, Error>> = tasks_create_first_row.collect().await;
+            let mut resp = resp.into_iter().collect::<Result<Vec<Fragment>, Error>>()?;
             super::generate_central_root_node(
                 folder,
                 n_fragments_first_row,
                 &resp,
                 &fragmentation_property,
                 &server_address,
-            );
+                &collection,
+            )?;
             let mut fragment_to_divide = resp.clone();
 
-            for i in 0..dept {
+            for _ in 0..dept {
                 let mut current_fragment = fragment_to_divide.pop();
-                let mut next_fragments_to_divide = Vec::with_capacity(n_fragments_first_row * (i + 1));
+                let mut next_fragments_to_divide =
+                    Vec::with_capacity(fragment_to_divide.len() * branching_factor);
                 while let Some(fragment) = current_fragment.as_mut() {
-                    let (fragment_1, fragment_2) = fragment
-                        .create_two_sub_fragment(&fragmentation_property, &server_address)
-                        .await;
-                    next_fragments_to_divide.push(fragment_1.clone());
-                    next_fragments_to_divide.push(fragment_2.clone());
-                    resp.push(fragment_1.clone());
-                    resp.push(fragment_2.clone());
+                    let sub_fragments = fragment
+                        .create_sub_fragments(
+                            branching_factor,
+                            &fragmentation_property,
+                            &server_address,
+                        )
+                        .await?;
+                    next_fragments_to_divide.extend(sub_fragments.iter().cloned());
+                    resp.extend(sub_fragments);
                     current_fragment = fragment_to_divide.pop();
                 }
                 fragment_to_divide.append(&mut next_fragments_to_divide);
             }
+            for fragment in resp.iter_mut() {
+                fragment.set_max_members(options.max_members_per_fragment);
+            }
             resp
         };
-        super::create_report(&fragments, &folder);
-        Self {
+        super::create_report(&fragments, &folder)?;
+        Ok(Self {
             fragments,
             max_size_cache,
-            random_generator: rand::rngs::StdRng::from_entropy(),
+            placement_policy: options.placement_policy.clone(),
+            leaf_depth: dept + 1,
+            random_generator: match options.seed {
+                Some(seed) => rand::rngs::StdRng::seed_from_u64(seed),
+                None => rand::rngs::StdRng::from_entropy(),
+            },
             folder: folder.clone(),
-        }
-    }
-
-    async fn materialize(&mut self) {
-        let materialize_tasks = futures_util::stream::FuturesUnordered::new();
-        for fragment in self.fragments.iter_mut() {
-            materialize_tasks.push(fragment.materialize());
-        }
-
-        let _: Vec<_> = materialize_tasks.collect().await;
+            server_address,
+            fragmentation_property,
+        })
     }
 }
 
 #[async_trait::async_trait]
 impl super::Fragmentation for Tree {
-    async fn insert(&mut self, member: &Member) {
+    async fn insert(&mut self, member: &Member) -> Result<(), Error> {
         let mut pos_candidate = Vec::new();
         for (i, fragment) in self.fragments.iter().enumerate() {
             if fragment.boundary().is_in_between(member.date) {
                 pos_candidate.push(i);
             }
         }
-        let pos = pos_candidate[self.random_generator.gen_range(0..pos_candidate.len())];
-        if let Err(_) = self.fragments[pos].insert(&member) {
-            self.materialize().await;
-            self.fragments[pos].insert(&member).unwrap();
+        if pos_candidate.is_empty() {
+            return Err(Error::Fragmentation(format!(
+                "no fragment covers the date of the member {}",
+                member.id
+            )));
+        }
+        let pos = match self.placement_policy {
+            PlacementPolicy::Random => {
+                pos_candidate[self.random_generator.gen_range(0..pos_candidate.len())]
+            }
+            PlacementPolicy::LeafOnly => *pos_candidate
+                .iter()
+                .find(|pos| self.fragments[**pos].depth() >= self.leaf_depth)
+                .ok_or(Error::Fragmentation(format!(
+                    "no leaf covers the date of the member {}",
+                    member.id
+                )))?,
+            PlacementPolicy::DeepestMatching => *pos_candidate
+                .iter()
+                .max_by_key(|pos| self.fragments[**pos].depth())
+                .ok_or(Error::Fragmentation(format!(
+                    "no fragment covers the date of the member {}",
+                    member.id
+                )))?,
+        };
+        if self.fragments[pos].insert(member).is_err() {
+            super::materialize_fragments(&mut self.fragments).await?;
+            self.fragments[pos]
+                .insert(member)
+                .map_err(|e| Error::Fragmentation(e.to_string()))?;
         }
+        super::split_full_fragment(
+            &mut self.fragments,
+            pos,
+            &self.fragmentation_property,
+            &self.server_address,
+        )
+        .await?;
+        Ok(())
     }
-    async fn finalize(&mut self) {
-        self.materialize().await;
+    async fn finalize(&mut self) -> Result<(), Error> {
+        super::materialize_fragments(&mut self.fragments).await?;
         self.print_summary();
-        super::create_report(&self.fragments, &self.folder);
+        super::create_report(&self.fragments, &self.folder)
     }
     fn max_size_cache(&self) -> usize {
         self.max_size_cache
@@ -0,0 +1,681 @@
+use crate::error::Error;
+use crate::parse_datadump::{on_quad, rename_blank_nodes_of_triple, InputFormat};
+use rio_api::parser::{QuadsParser, TriplesParser};
+use std::io::BufRead;
+use std::mem;
+use std::path::Path;
+
+/// The number of statements of Turtle or TriG parsed at once, the statements after a syntax error
+/// are parsed again so it bounds the work lost to an error
+const STATEMENTS_PER_CHUNK: usize = 10_000;
+
+/// The syntax errors skipped by the lenient parsing, the parsing is aborted
+/// when there are more errors than the budget
+pub struct SyntaxErrorBudget {
+    max_syntax_errors: usize,
+    n_syntax_errors: usize,
+}
+
+impl SyntaxErrorBudget {
+    pub fn new(max_syntax_errors: usize) -> Self {
+        Self {
+            max_syntax_errors,
+            n_syntax_errors: 0,
+        }
+    }
+
+    pub fn n_syntax_errors(&self) -> usize {
+        self.n_syntax_errors
+    }
+
+    /// Log and skip a syntax error, the error is returned once the budget is exceeded
+    fn skip(&mut self, error: Error) -> Result<(), Error> {
+        self.n_syntax_errors += 1;
+        if self.n_syntax_errors > self.max_syntax_errors {
+            return Err(match error {
+                Error::RdfSyntax {
+                    path,
+                    line,
+                    column,
+                    message,
+                } => Error::RdfSyntax {
+                    path,
+                    line,
+                    column,
+                    message: format!(
+                        "{message}, the parsing stops after {} syntax errors",
+                        self.max_syntax_errors
+                    ),
+                },
+                error => error,
+            });
+        }
+        eprintln!("Skipping a malformed statement, {error}");
+        Ok(())
+    }
+}
+
+/// Parse a RDF document skipping its malformed statements. The lines of N-Triples and N-Quads
+/// are parsed one by one, the Turtle and TriG documents are split into statements
+/// and the parsing resumes after the statement of each syntax error.
+/// None of the triples of a malformed statement are kept.
+pub fn parse_rdf_leniently(
+    reader: impl BufRead,
+    input_format: &InputFormat,
+    data_dump_path: &Path,
+    budget: &mut SyntaxErrorBudget,
+    on_triple: &mut impl FnMut(
+        &rio_api::model::Triple,
+        Option<&rio_api::model::GraphName>,
+    ) -> Result<(), Error>,
+) -> Result<(), Error> {
+    match input_format {
+        InputFormat::NTriples | InputFormat::NQuads => {
+            parse_lines(reader, input_format, data_dump_path, budget, on_triple)
+        }
+        InputFormat::Turtle | InputFormat::TriG => {
+            parse_statements(reader, input_format, data_dump_path, budget, on_triple)
+        }
+    }
+}
+
+/// Parse a line-based document line by line
+fn parse_lines(
+    mut reader: impl BufRead,
+    input_format: &InputFormat,
+    data_dump_path: &Path,
+    budget: &mut SyntaxErrorBudget,
+    on_triple: &mut impl FnMut(
+        &rio_api::model::Triple,
+        Option<&rio_api::model::GraphName>,
+    ) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let mut line = Vec::new();
+    let mut line_number = 0u64;
+    loop {
+        line.clear();
+        let n_bytes = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|e| Error::io(data_dump_path, e))?;
+        if n_bytes == 0 {
+            return Ok(());
+        }
+        line_number += 1;
+        // the line is checked before its triples are sent, so that a malformed line is dropped as a whole
+        match parse_document(&line, input_format, &mut |_, _| Ok(())) {
+            Ok(()) => parse_document(&line, input_format, on_triple)?,
+            Err(Error::RdfSyntax {
+                column, message, ..
+            }) => budget.skip(Error::RdfSyntax {
+                path: Some(data_dump_path.to_path_buf()),
+                line: Some(line_number),
+                column,
+                message,
+            })?,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// A statement of a Turtle or a TriG document
+struct Statement {
+    text: String,
+    /// The line of the start of the statement starting from 1
+    line: u64,
+    /// The byte of the start of the statement in its line starting from 1
+    column: u64,
+    /// The index of the statement in the directives if it declares a prefix or the base IRI
+    directive: Option<usize>,
+    /// The number of directives declared before the statement
+    n_directives_before: usize,
+}
+
+/// Parse a Turtle or a TriG document by chunks of statements, after a syntax error the parsing
+/// resumes with the next statement and the directives declared before it
+fn parse_statements(
+    mut reader: impl BufRead,
+    input_format: &InputFormat,
+    data_dump_path: &Path,
+    budget: &mut SyntaxErrorBudget,
+    on_triple: &mut impl FnMut(
+        &rio_api::model::Triple,
+        Option<&rio_api::model::GraphName>,
+    ) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let mut splitter = StatementSplitter::default();
+    let mut directives: Vec<String> = Vec::new();
+    let mut statements: Vec<Statement> = Vec::with_capacity(STATEMENTS_PER_CHUNK);
+    let mut n_parsers = 0usize;
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let n_bytes = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|e| Error::io(data_dump_path, e))?;
+        let completed_statements = if n_bytes == 0 {
+            splitter.finish()
+        } else {
+            splitter.push_line(&String::from_utf8_lossy(&line))
+        };
+        for (text, line_number, column) in completed_statements {
+            let n_directives_before = directives.len();
+            let directive = if is_directive(&text) {
+                directives.push(text.clone());
+                Some(n_directives_before)
+            } else {
+                None
+            };
+            statements.push(Statement {
+                text,
+                line: line_number,
+                column,
+                directive,
+                n_directives_before,
+            });
+        }
+        if n_bytes == 0 || statements.len() >= STATEMENTS_PER_CHUNK {
+            parse_chunk(
+                &statements,
+                &mut directives,
+                input_format,
+                data_dump_path,
+                budget,
+                &mut n_parsers,
+                on_triple,
+            )?;
+            statements.clear();
+        }
+        if n_bytes == 0 {
+            return Ok(());
+        }
+    }
+}
+
+/// Parse a chunk of statements, each syntax error restarts the parser after its statement
+fn parse_chunk(
+    statements: &[Statement],
+    directives: &mut [String],
+    input_format: &InputFormat,
+    data_dump_path: &Path,
+    budget: &mut SyntaxErrorBudget,
+    n_parsers: &mut usize,
+    on_triple: &mut impl FnMut(
+        &rio_api::model::Triple,
+        Option<&rio_api::model::GraphName>,
+    ) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let mut first = 0;
+    while first < statements.len() {
+        let mut document = String::new();
+        for directive in &directives[..statements[first].n_directives_before] {
+            document.push_str(directive);
+            document.push('\n');
+        }
+        let header_len = document.len();
+        let mut starts = Vec::with_capacity(statements.len() - first);
+        for statement in &statements[first..] {
+            starts.push(document.len());
+            document.push_str(&statement.text);
+        }
+
+        // the chunk is checked before its triples are sent, so that a malformed statement is dropped as a whole
+        let result = parse_document(document.as_bytes(), input_format, &mut |_, _| Ok(()));
+        let (line, column, message) = match result {
+            Ok(()) => {
+                return parse_renamed_document(
+                    document.as_bytes(),
+                    input_format,
+                    n_parsers,
+                    on_triple,
+                )
+                .map_err(|error| error.in_file(data_dump_path))
+            }
+            Err(Error::RdfSyntax {
+                line: Some(line),
+                column,
+                message,
+                ..
+            }) => (line, column.unwrap_or(1), message),
+            Err(error) => return Err(error.in_file(data_dump_path)),
+        };
+
+        let offset = byte_offset(&document, line, column);
+        if offset < header_len {
+            // the directives have already been parsed, so the error cannot be attributed to a statement
+            return Err(Error::RdfSyntax {
+                path: Some(data_dump_path.to_path_buf()),
+                line: None,
+                column: None,
+                message,
+            });
+        }
+        let i = starts.partition_point(|start| *start <= offset) - 1;
+        if i > 0 {
+            parse_renamed_document(
+                &document.as_bytes()[..starts[i]],
+                input_format,
+                n_parsers,
+                on_triple,
+            )
+            .map_err(|error| error.in_file(data_dump_path))?;
+        }
+        let statement = &statements[first + i];
+        let before_error =
+            &statement.text.as_bytes()[..(offset - starts[i]).min(statement.text.len())];
+        let n_lines = before_error.iter().filter(|byte| **byte == b'\n').count() as u64;
+        let (line, column) = match before_error.iter().rposition(|byte| *byte == b'\n') {
+            Some(j) => (statement.line + n_lines, (before_error.len() - j) as u64),
+            None => (statement.line, statement.column + before_error.len() as u64),
+        };
+        budget.skip(Error::RdfSyntax {
+            path: Some(data_dump_path.to_path_buf()),
+            line: Some(line),
+            column: Some(column),
+            message,
+        })?;
+        // a malformed directive is not declared again for the next statements
+        if let Some(directive) = statement.directive {
+            directives[directive].clear();
+        }
+        first += i + 1;
+    }
+    Ok(())
+}
+
+/// Parse a document in one of the formats
+fn parse_document(
+    document: &[u8],
+    input_format: &InputFormat,
+    on_triple: &mut impl FnMut(
+        &rio_api::model::Triple,
+        Option<&rio_api::model::GraphName>,
+    ) -> Result<(), Error>,
+) -> Result<(), Error> {
+    match input_format {
+        InputFormat::NTriples => {
+            rio_turtle::NTriplesParser::new(document).parse_all(&mut |t| on_triple(&t, None))
+        }
+        InputFormat::NQuads => {
+            rio_turtle::NQuadsParser::new(document).parse_all(&mut |q| on_quad(q, on_triple))
+        }
+        InputFormat::Turtle => {
+            rio_turtle::TurtleParser::new(document, None).parse_all(&mut |t| on_triple(&t, None))
+        }
+        InputFormat::TriG => {
+            rio_turtle::TriGParser::new(document, None).parse_all(&mut |q| on_quad(q, on_triple))
+        }
+    }
+}
+
+/// Parse a part of a document with a new parser, every parser numbers the blank nodes
+/// it generates from zero so they are suffixed with the number of the parser
+fn parse_renamed_document(
+    document: &[u8],
+    input_format: &InputFormat,
+    n_parsers: &mut usize,
+    on_triple: &mut impl FnMut(
+        &rio_api::model::Triple,
+        Option<&rio_api::model::GraphName>,
+    ) -> Result<(), Error>,
+) -> Result<(), Error> {
+    *n_parsers += 1;
+    let n_parser = *n_parsers;
+    let rename = |id: &str| is_generated_blank_node_id(id).then(|| format!("{id}_{n_parser}"));
+    parse_document(document, input_format, &mut |t, graph_name| {
+        rename_blank_nodes_of_triple(t, graph_name, &rename, on_triple)
+    })
+}
+
+/// The byte offset in a document of a line and of a byte in this line starting from 1
+fn byte_offset(document: &str, line: u64, column: u64) -> usize {
+    let line_start = match line {
+        0 | 1 => 0,
+        line => document
+            .match_indices('\n')
+            .nth(line as usize - 2)
+            .map_or(document.len(), |(i, _)| i + 1),
+    };
+    (line_start + column.saturating_sub(1) as usize).min(document.len())
+}
+
+/// The parsers of rio label the blank nodes they generate with "riog" followed by 8 digits
+fn is_generated_blank_node_id(id: &str) -> bool {
+    id.len() == 12 && id.starts_with("riog") && id[4..].bytes().all(|byte| byte.is_ascii_digit())
+}
+
+/// The statement without its leading whitespaces and comments
+fn content(text: &str) -> &str {
+    let mut text = text.trim_start();
+    while text.starts_with('#') {
+        text = text.find('\n').map_or("", |i| &text[i..]).trim_start();
+    }
+    text
+}
+
+/// The statement declares a prefix or the base IRI
+fn is_directive(text: &str) -> bool {
+    let content = content(text);
+    content.starts_with("@prefix") || content.starts_with("@base") || is_sparql_directive(content)
+}
+
+/// The statement declares a prefix or the base IRI with the SPARQL syntax, it has no final dot
+fn is_sparql_directive(content: &str) -> bool {
+    let keyword = content
+        .split(|c: char| c.is_whitespace() || c == '<')
+        .next()
+        .unwrap_or_default();
+    keyword.eq_ignore_ascii_case("prefix") || keyword.eq_ignore_ascii_case("base")
+}
+
+/// Split a Turtle or a TriG document into its statements. A statement ends with a dot
+/// or with the brace closing a graph, outside of the IRIs, the literals, the comments
+/// and the nested blank nodes and collections.
+#[derive(Default)]
+struct StatementSplitter {
+    statement: String,
+    /// the line and the byte in the line of the start of the current statement
+    line: u64,
+    column: u64,
+    n_lines: u64,
+    /// the number of opened brackets and parentheses
+    depth: usize,
+    n_braces: usize,
+    is_in_iri: bool,
+    /// the quote of the current literal and whether it is a long literal
+    literal: Option<(u8, bool)>,
+    is_escaped: bool,
+}
+
+impl StatementSplitter {
+    /// Add the next line of the document, return the statements it completes
+    /// with the line and the byte of their start
+    fn push_line(&mut self, line: &str) -> Vec<(String, u64, u64)> {
+        self.n_lines += 1;
+        if self.statement.is_empty() {
+            self.line = self.n_lines;
+            self.column = 1;
+        }
+        let bytes = line.as_bytes();
+        let mut resp = Vec::new();
+        let mut start = 0;
+        let mut is_in_comment = false;
+        let mut i = 0;
+        while i < bytes.len() {
+            let byte = bytes[i];
+            let mut is_end_of_statement = false;
+            if is_in_comment {
+            } else if let Some((quote, is_long)) = self.literal {
+                if self.is_escaped {
+                    self.is_escaped = false;
+                } else if byte == b'\\' {
+                    self.is_escaped = true;
+                } else if byte == quote && (!is_long || bytes[i..].starts_with(&[quote; 3])) {
+                    if is_long {
+                        i += 2;
+                    }
+                    self.literal = None;
+                } else if byte == b'\n' && !is_long {
+                    // a short literal cannot span several lines
+                    self.literal = None;
+                }
+            } else if self.is_in_iri {
+                if byte == b'>' {
+                    self.is_in_iri = false;
+                    if self.depth == 0 && self.n_braces == 0 {
+                        let statement_start = if content(&self.statement).is_empty() {
+                            &line[start..i]
+                        } else {
+                            &self.statement
+                        };
+                        is_end_of_statement = is_sparql_directive(content(statement_start));
+                    }
+                } else if byte == b'\n' {
+                    self.is_in_iri = false;
+                }
+            } else {
+                match byte {
+                    b'#' => is_in_comment = true,
+                    b'<' => self.is_in_iri = true,
+                    b'"' | b'\'' => {
+                        let is_long = bytes[i..].starts_with(&[byte; 3]);
+                        if is_long {
+                            i += 2;
+                        }
+                        self.literal = Some((byte, is_long));
+                    }
+                    b'[' | b'(' => self.depth += 1,
+                    b']' | b')' => self.depth = self.depth.saturating_sub(1),
+                    b'{' => self.n_braces += 1,
+                    b'}' => {
+                        self.n_braces = self.n_braces.saturating_sub(1);
+                        is_end_of_statement = self.n_braces == 0 && self.depth == 0;
+                    }
+                    b'.' => {
+                        // the dots of the decimals and of the prefixed names are followed by a character
+                        is_end_of_statement = self.depth == 0
+                            && self.n_braces == 0
+                            && bytes
+                                .get(i + 1)
+                                .is_none_or(|next| next.is_ascii_whitespace() || *next == b'#');
+                    }
+                    _ => {}
+                }
+            }
+            i += 1;
+            if is_end_of_statement {
+                self.statement.push_str(&line[start..i]);
+                resp.push((mem::take(&mut self.statement), self.line, self.column));
+                start = i;
+                self.line = self.n_lines;
+                self.column = i as u64 + 1;
+            }
+        }
+        self.statement.push_str(&line[start..]);
+        resp
+    }
+
+    /// Return the last statement of the document if it is not terminated
+    fn finish(&mut self) -> Vec<(String, u64, u64)> {
+        let statement = mem::take(&mut self.statement);
+        if content(&statement).is_empty() {
+            Vec::new()
+        } else {
+            vec![(statement, self.line, self.column)]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(document: &str) -> Vec<(String, u64, u64)> {
+        let mut splitter = StatementSplitter::default();
+        let mut resp = Vec::new();
+        for line in document.split_inclusive('\n') {
+            resp.append(&mut splitter.push_line(line));
+        }
+        resp.append(&mut splitter.finish());
+        resp
+    }
+
+    fn texts(document: &str) -> Vec<String> {
+        split(document)
+            .into_iter()
+            .map(|(text, _, _)| text.trim().to_string())
+            .collect()
+    }
+
+    fn parse(
+        document: &str,
+        input_format: InputFormat,
+        max_syntax_errors: usize,
+    ) -> Result<Vec<String>, Error> {
+        let mut budget = SyntaxErrorBudget::new(max_syntax_errors);
+        let mut triples = Vec::new();
+        parse_rdf_leniently(
+            document.as_bytes(),
+            &input_format,
+            Path::new("test.ttl"),
+            &mut budget,
+            &mut |t, _| {
+                triples.push(t.to_string());
+                Ok(())
+            },
+        )?;
+        Ok(triples)
+    }
+
+    #[test]
+    fn the_dots_of_the_terms_do_not_end_a_statement() {
+        let document =
+            "ex:a ex:p ex:i.j .\nex:b ex:p 1.5 .\n<http://a.b/#c> ex:p <http://x.y/z.> .\n";
+        assert_eq!(
+            texts(document),
+            vec![
+                "ex:a ex:p ex:i.j .",
+                "ex:b ex:p 1.5 .",
+                "<http://a.b/#c> ex:p <http://x.y/z.> ."
+            ]
+        );
+    }
+
+    #[test]
+    fn the_literals_and_the_comments_do_not_end_a_statement() {
+        let document = "ex:a ex:p \"a. b\" . # a comment. with dots\nex:b ex:p \"\"\"long\n. text\"\"\" .\nex:c ex:p 'it\\'s. ' .\n";
+        assert_eq!(
+            texts(document),
+            vec![
+                "ex:a ex:p \"a. b\" .",
+                "# a comment. with dots\nex:b ex:p \"\"\"long\n. text\"\"\" .",
+                "ex:c ex:p 'it\\'s. ' ."
+            ]
+        );
+    }
+
+    #[test]
+    fn the_nested_blank_nodes_and_collections_are_part_of_the_statement() {
+        let document = "ex:a ex:p [ ex:q ( 1 2 ) ; ex:r [ ex:s 3 ] ] .\nex:b ex:p ex:c .";
+        assert_eq!(
+            texts(document),
+            vec![
+                "ex:a ex:p [ ex:q ( 1 2 ) ; ex:r [ ex:s 3 ] ] .",
+                "ex:b ex:p ex:c ."
+            ]
+        );
+    }
+
+    #[test]
+    fn the_sparql_directives_end_with_their_iri() {
+        let document = "PREFIX ex: <http://ex.org/>\nprefix ey: <http://ey.org/> ex:a ex:p ex:b .\nBASE <http://base.org/>";
+        assert_eq!(
+            texts(document),
+            vec![
+                "PREFIX ex: <http://ex.org/>",
+                "prefix ey: <http://ey.org/>",
+                "ex:a ex:p ex:b .",
+                "BASE <http://base.org/>"
+            ]
+        );
+        assert!(is_directive("# the prefixes\nPREFIX ex: <http://ex.org/>"));
+        assert!(is_directive("@prefix ex: <http://ex.org/> ."));
+        assert!(!is_directive("ex:prefix ex:p ex:b ."));
+    }
+
+    #[test]
+    fn a_graph_is_one_statement_ending_with_its_brace() {
+        let document =
+            "GRAPH ex:g {\n  ex:a ex:p ex:b .\n  ex:c ex:p ex:d .\n}\nex:h { ex:e ex:p ex:f }\n";
+        assert_eq!(
+            texts(document),
+            vec![
+                "GRAPH ex:g {\n  ex:a ex:p ex:b .\n  ex:c ex:p ex:d .\n}",
+                "ex:h { ex:e ex:p ex:f }"
+            ]
+        );
+    }
+
+    #[test]
+    fn the_statements_are_located_by_their_start() {
+        let statements = split("ex:a ex:p ex:b . ex:c ex:p ex:d .\n\nex:e ex:p\n  ex:f .\nex:g");
+        let positions: Vec<(u64, u64)> = statements
+            .iter()
+            .map(|(_, line, column)| (*line, *column))
+            .collect();
+        assert_eq!(positions, vec![(1, 1), (1, 17), (1, 34), (4, 9)]);
+        assert_eq!(statements[3].0, "\nex:g");
+    }
+
+    #[test]
+    fn byte_offset_counts_from_one() {
+        let document = "ab\ncde\n\nf";
+        assert_eq!(byte_offset(document, 1, 1), 0);
+        assert_eq!(byte_offset(document, 2, 2), 4);
+        assert_eq!(byte_offset(document, 4, 1), 8);
+        assert_eq!(byte_offset(document, 9, 1), document.len());
+        assert_eq!(byte_offset(document, 2, 100), document.len());
+    }
+
+    #[test]
+    fn only_the_blank_nodes_generated_by_rio_are_renamed() {
+        assert!(is_generated_blank_node_id("riog00000001"));
+        assert!(!is_generated_blank_node_id("riog0000001"));
+        assert!(!is_generated_blank_node_id("riogabcdefgh"));
+        assert!(!is_generated_blank_node_id("b0"));
+    }
+
+    #[test]
+    fn the_malformed_statements_are_dropped_as_a_whole() {
+        let document = "@prefix ex: <http://ex.org/> .\nex:a ex:p 1 .\nex:b ex:p 2 ; ex:q 2 2 .\nex:c ex:p 3 .\n";
+        let triples = parse(document, InputFormat::Turtle, 1).unwrap();
+        assert_eq!(
+            triples,
+            vec![
+                "<http://ex.org/a> <http://ex.org/p> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer>",
+                "<http://ex.org/c> <http://ex.org/p> \"3\"^^<http://www.w3.org/2001/XMLSchema#integer>"
+            ]
+        );
+    }
+
+    #[test]
+    fn the_malformed_lines_are_dropped_as_a_whole() {
+        let document = "<http://ex.org/a> <http://ex.org/p> <http://ex.org/b> .\n<http://ex.org/c> <http://ex.org/p> <http://ex.org/d> . <x\n";
+        let triples = parse(document, InputFormat::NTriples, 1).unwrap();
+        assert_eq!(
+            triples,
+            vec!["<http://ex.org/a> <http://ex.org/p> <http://ex.org/b>"]
+        );
+    }
+
+    #[test]
+    fn the_generated_blank_nodes_are_distinct_across_the_parsers() {
+        let document = "@prefix ex: <http://ex.org/> .\nex:a ex:p [ ex:q 1 ] .\nex:b ex:p ex:q ex:r .\nex:c ex:p [ ex:q 2 ] .\n";
+        let triples = parse(document, InputFormat::Turtle, 1).unwrap();
+        let blank_nodes: Vec<&str> = triples
+            .iter()
+            .filter(|triple| triple.starts_with("<http://ex.org/"))
+            .map(|triple| triple.rsplit(' ').next().unwrap())
+            .collect();
+        assert_eq!(blank_nodes.len(), 2);
+        assert_ne!(blank_nodes[0], blank_nodes[1]);
+        assert!(blank_nodes.iter().all(|id| id.starts_with("_:riog")));
+        for blank_node in blank_nodes {
+            assert!(triples
+                .iter()
+                .any(|triple| triple.starts_with(&format!("{blank_node} "))));
+        }
+    }
+
+    #[test]
+    fn the_errors_are_located_in_the_data_dump() {
+        let document = "@prefix ex: <http://ex.org/> .\nex:a ex:p 1 .\nex:b ex:p\n  ex:c ex:d .\n";
+        match parse(document, InputFormat::Turtle, 0) {
+            Err(Error::RdfSyntax {
+                line: Some(4),
+                column: Some(column),
+                ..
+            }) => assert!(column >= 3, "column {column}"),
+            result => panic!("unexpected result {:?}", result.map(|_| ())),
+        }
+    }
+}
@@ -1,93 +1,258 @@
 mod cli;
+mod compression;
 mod config;
+mod date;
+mod error;
 mod fragmentation;
+mod lenient;
 mod member;
+mod member_extraction;
 mod parse_datadump;
+mod shape;
 mod tree;
 
 use clap::Parser;
 use cli::*;
 use config::*;
-use fragmentation::FragmentationTypeName;
-use futures;
+use error::Error;
+use fragmentation::b_plus_tree_fragmentation::BPlusTreeParameters;
+use fragmentation::tree::PlacementPolicy;
+use fragmentation::{FragmentationOptions, FragmentationTypeName};
 use futures::stream::StreamExt;
-use glob;
 use humantime::format_duration;
+use member_extraction::MemberExtractionTypeName;
 use parse_datadump::*;
 use std::path::PathBuf;
 use std::time;
-use tokio;
+use tree::OutputFormat;
 
 #[tokio::main]
 async fn main() {
+    if let Err(error) = run().await {
+        eprintln!("{error}");
+        std::process::exit(error.exit_code());
+    }
+}
+
+async fn run() -> Result<(), Error> {
     let start = time::Instant::now();
     let cli = Cli::parse();
     let config_path = cli.config_path.unwrap_or(PathBuf::from("./config.json"));
-    let data_injection_config = Config::new(config_path);
+    let mut data_injection_config = Config::new(config_path.clone())?;
     let notice_frequency = cli.frequency_notification;
     let n_fragments_first_row = cli.n_fragment_first_row;
     if n_fragments_first_row < 2 {
-        panic!("The should be at least 2 fragments")
+        return Err(Error::Config(
+            "there should be at least 2 fragments in the first row".to_string(),
+        ));
     }
     let dept = cli.dept;
     if let Some(dept) = dept {
-        if dept <= 0 {
-            panic!("the dept should be at least of 1")
+        if dept == 0 {
+            return Err(Error::Config(
+                "the dept should be at least of 1".to_string(),
+            ));
         }
     }
+    let branching_factor = cli.branching_factor;
+    if branching_factor < 2 {
+        return Err(Error::Config(
+            "the branching factor should be at least of 2".to_string(),
+        ));
+    }
+    let max_members_per_fragment = cli.max_members_per_fragment;
+    if max_members_per_fragment == Some(0) {
+        return Err(Error::Config(
+            "the maximum number of members per fragment should be at least of 1".to_string(),
+        ));
+    }
+    let fill_factor = cli.fill_factor;
+    if !(0.0..=1.0).contains(&fill_factor) {
+        return Err(Error::Config(
+            "the fill factor should be between 0 and 1".to_string(),
+        ));
+    }
 
-    let max_cache_element: usize = if data_injection_config.n_members / (n_fragments_first_row * 20) != 0usize
-    {
-        data_injection_config.n_members / (n_fragments_first_row * 20)
+    let data_dump_paths = if cli.data_dump_path.is_empty() {
+        vec![PathBuf::from(
+            "../comunica_filter_benchmark/evaluation/data/dahcc_1_participant/data.ttl",
+        )]
     } else {
-        1usize
+        expand_data_dump_paths(&cli.data_dump_path)?
     };
-    let out_path = cli.output_path.unwrap_or(PathBuf::from("./generated"));
-    delete_previous_file(&out_path).await;
-
-    let data_dump_path = cli.data_dump_path.unwrap_or(PathBuf::from(
-        "../comunica_filter_benchmark/evaluation/data/dahcc_1_participant/data.ttl",
-    ));
-    let large_file = cli.large_file;
+    let input_format = cli.input_format.map(InputFormat::try_from).transpose()?;
+    let output_named_graphs = cli.output_named_graphs;
+    let max_syntax_errors = if cli.lenient {
+        Some(cli.max_syntax_errors)
+    } else {
+        None
+    };
+    let ldes = cli.ldes;
     let fragmentation_type = if let Some(frag) = cli.fragmentation {
-        FragmentationTypeName::from(frag)
+        FragmentationTypeName::try_from(frag)?
     } else {
         FragmentationTypeName::OneAryTree
     };
+    let placement_policy = if let Some(placement) = cli.placement {
+        PlacementPolicy::try_from(placement)?
+    } else {
+        PlacementPolicy::Random
+    };
+    let member_extraction_type = if let Some(member_extraction) = cli.member_extraction {
+        MemberExtractionTypeName::try_from(member_extraction)?
+    } else {
+        MemberExtractionTypeName::SchemaComplete
+    };
+    let input = InputOptions {
+        data_dump_paths,
+        input_format,
+        large_file: cli.large_file,
+        max_syntax_errors,
+        member_extraction_type,
+    };
+
+    if cli.update_config && !cli.pre_scan {
+        return Err(Error::Config(
+            "the config can only be updated with the statistics of a pre-scan".to_string(),
+        ));
+    }
+    let is_b_plus_tree = matches!(fragmentation_type, FragmentationTypeName::BPlusTree);
+    if is_b_plus_tree && !cli.pre_scan {
+        return Err(Error::Config(
+            "the B+tree fragmentation is built from the distribution of the dates found with --pre-scan"
+                .to_string(),
+        ));
+    }
+    let mut date_sample = None;
+    if cli.pre_scan {
+        let missing_statistics = data_injection_config.n_members.is_none()
+            || data_injection_config.lowest_date.is_none()
+            || data_injection_config.highest_date.is_none();
+        if missing_statistics || cli.update_config || is_b_plus_tree {
+            println!("--- Pre-scan of the data dump ---");
+            let statistics = pre_scan(&input, &data_injection_config)?;
+            println!(
+                "{} members from {} to {}",
+                statistics.n_members, statistics.lowest_date, statistics.highest_date
+            );
+            data_injection_config.n_members = Some(statistics.n_members);
+            data_injection_config.lowest_date = Some(statistics.lowest_date);
+            data_injection_config.highest_date = Some(statistics.highest_date);
+            date_sample = Some(statistics.date_sample);
+            if cli.update_config {
+                data_injection_config.save(config_path)?;
+            }
+        }
+    }
+    let n_members = data_injection_config.n_members.ok_or(Error::Config(
+        "the number of members should be defined in the config or found with --pre-scan"
+            .to_string(),
+    ))?;
+    if data_injection_config.lowest_date.is_none() || data_injection_config.highest_date.is_none() {
+        return Err(Error::Config(
+            "the lowest and the highest date should be defined in the config or found with --pre-scan"
+                .to_string(),
+        ));
+    }
+
+    let max_cache_element: usize = if n_members / (n_fragments_first_row * 20) != 0usize {
+        n_members / (n_fragments_first_row * 20)
+    } else {
+        1usize
+    };
+    let b_plus_tree_parameters = date_sample.map(|date_sample| BPlusTreeParameters {
+        fan_out: cli.fan_out,
+        page_size: cli.page_size,
+        n_members,
+        date_sample,
+    });
+    let out_path = cli.output_path.unwrap_or(PathBuf::from("./generated"));
+    delete_previous_file(&out_path).await?;
+
+    let fragmentation_options = FragmentationOptions {
+        fragmentation_type,
+        n_fragments_first_row,
+        max_size_cache: max_cache_element,
+        dept,
+        branching_factor,
+        placement_policy,
+        seed: cli.seed,
+        b_plus_tree_parameters,
+        max_members_per_fragment,
+        fill_factor,
+    };
 
     parse_datadump(
-        data_dump_path,
+        &input,
         &data_injection_config,
         notice_frequency,
-        large_file,
-        max_cache_element,
-        n_fragments_first_row,
         out_path,
-        fragmentation_type,
-        dept
-    )
-    .unwrap();
+        fragmentation_options,
+        ldes,
+        output_named_graphs,
+    )?;
     let duration = start.elapsed();
 
     println!("Time elapsed is {}", format_duration(duration));
     println!("--- Fragmentation finished---");
+    Ok(())
+}
+
+/// Expand the glob patterns of the data dump paths
+fn expand_data_dump_paths(patterns: &Vec<String>) -> Result<Vec<PathBuf>, Error> {
+    let mut resp = Vec::new();
+    for pattern in patterns {
+        if pattern == STDIN_PATH {
+            resp.push(PathBuf::from(STDIN_PATH));
+            continue;
+        }
+        let mut paths = glob::glob(pattern)
+            .map_err(|e| {
+                Error::Config(format!("the data dump pattern {pattern} is not valid: {e}"))
+            })?
+            .map(|path| {
+                path.map_err(|e| {
+                    let path = e.path().to_path_buf();
+                    Error::io(&path, e.into_error())
+                })
+            })
+            .collect::<Result<Vec<PathBuf>, Error>>()?;
+        if paths.is_empty() {
+            return Err(Error::Config(format!("no data dump matches {pattern}")));
+        }
+        resp.append(&mut paths);
+    }
+    Ok(resp)
 }
 
-async fn delete_previous_file(out_path: &PathBuf) {
+async fn delete_previous_file(out_path: &PathBuf) -> Result<(), Error> {
     let mut tasks = Vec::new();
-    println!("{}/*.ttl", out_path.as_path().to_str().unwrap());
-    for path in glob::glob(&format!("{}/*.ttl", out_path.as_path().to_str().unwrap())).unwrap() {
-        tasks.push(async {
-            match path {
-                Ok(path) => {
-                    println!("Removing file: {:?}", path.display());
-                    std::fs::remove_file(path).unwrap();
+    for output_format in [OutputFormat::Turtle, OutputFormat::TriG] {
+        let pattern = format!("{}/*.{}", out_path.display(), output_format.extension());
+        println!("{pattern}");
+        let paths = glob::glob(&pattern).map_err(|e| {
+            Error::Config(format!(
+                "the output path {} is not valid: {e}",
+                out_path.display()
+            ))
+        })?;
+        for path in paths {
+            tasks.push(async {
+                match path {
+                    Ok(path) => {
+                        println!("Removing file: {:?}", path.display());
+                        std::fs::remove_file(&path).map_err(|e| Error::io(&path, e))
+                    }
+                    Err(e) => {
+                        let path = e.path().to_path_buf();
+                        Err(Error::io(&path, e.into_error()))
+                    }
                 }
-                Err(e) => panic!("{e}"),
-            }
-        });
+            });
+        }
     }
     let task_stream: futures_util::stream::FuturesUnordered<_> = tasks.into_iter().collect();
 
-    let _: Vec<_> = task_stream.collect().await;
+    let results: Vec<Result<(), Error>> = task_stream.collect().await;
+    results.into_iter().collect()
 }
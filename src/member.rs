@@ -3,26 +3,38 @@ use std::fmt;
 #[derive(Default, Debug, Clone)]
 pub struct Member {
     pub properties: Vec<String>,
+    /// The date of the member in nanoseconds since the Unix epoch
     pub date: i64,
+    /// The index of the date field of the date in the config, [None] if the member has no date
+    pub date_priority: Option<usize>,
     pub id: String,
+    /// The named graph of the member, the member is written inside it if it is defined
+    pub graph: Option<String>,
 }
 
-impl Member{
-    pub fn new(n_properties: usize) -> Self{
-        Self{
+impl Member {
+    pub fn new(n_properties: usize) -> Self {
+        Self {
             properties: Vec::with_capacity(n_properties),
             ..Default::default()
         }
     }
 }
 
-impl fmt::Display for Member{
+impl fmt::Display for Member {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut resp = String::new();
-        for m in self.properties.iter(){
+        if let Some(graph) = &self.graph {
+            resp.push_str(graph);
+            resp.push_str(" {\n");
+        }
+        for m in self.properties.iter() {
             resp.push_str(m.as_str());
             resp.push_str("\n");
         }
+        if self.graph.is_some() {
+            resp.push_str("}\n");
+        }
         write!(f, "{}", resp)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,127 @@
+use super::*;
+use crate::config::Config;
+use crate::error::Error;
+use crate::member::Member;
+use regex;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::mem;
+
+/// A member is the Concise Bounded Description (CBD) of an IRI respecting the member IRI regex,
+/// its outgoing triples and recursively those of its blank nodes. The named nodes are followed up
+/// to the CBD depth of the [Config]. The CBD needs the whole data dump, so the members are
+/// returned at the end of it.
+/// https://www.w3.org/submissions/CBD/
+pub struct ConciseBoundedDescriptionExtraction {
+    /// the triples of the data dump indexed by subject
+    triples_by_subject: HashMap<String, Vec<MemberTriple>>,
+    /// the member IRIs in the order they appear in the data dump
+    member_ids: Vec<String>,
+    member_id_set: HashSet<String>,
+    re_member_id: regex::Regex,
+    schema: Vec<Schema>,
+    shape: Option<NodeShape>,
+    date_field: Vec<String>,
+    date_formats: Vec<String>,
+    depth: usize,
+    data_quality: DataQualityReport,
+    rejected_members: Vec<RejectedMember>,
+}
+
+impl ConciseBoundedDescriptionExtraction {
+    pub fn new(config: &Config, shape: Option<NodeShape>) -> Result<Self, Error> {
+        Ok(Self {
+            triples_by_subject: HashMap::new(),
+            member_ids: Vec::new(),
+            member_id_set: HashSet::new(),
+            re_member_id: member_url_regex(config)?,
+            schema: config.schema.clone(),
+            shape,
+            date_field: config.date_field.clone(),
+            date_formats: config.date_formats.clone(),
+            depth: config.cbd_depth,
+            data_quality: DataQualityReport::default(),
+            rejected_members: Vec::new(),
+        })
+    }
+
+    /// Build the member from the description of its IRI alongside the violations of the schema and the shape
+    fn describe(&self, id: &str) -> (Member, Vec<String>) {
+        let mut member = Member::new(self.schema.len());
+        member.id = id.to_string();
+        let mut validator = MemberValidator::new(self.schema.clone(), self.shape.clone());
+        let mut visited = HashSet::from([id.to_string()]);
+        let mut to_describe = VecDeque::from([(id.to_string(), 0usize)]);
+
+        while let Some((subject, depth)) = to_describe.pop_front() {
+            for member_triple in self.triples_by_subject.get(&subject).into_iter().flatten() {
+                member_triple.push_to(&mut member);
+                validator.validate(member_triple, &member.id);
+
+                if let Some(object) = &member_triple.linked_object {
+                    // the other members are described by their own CBD
+                    if visited.contains(object) || self.member_id_set.contains(object) {
+                        continue;
+                    }
+                    if object.starts_with("_:") {
+                        visited.insert(object.clone());
+                        to_describe.push_back((object.clone(), depth));
+                    } else if depth < self.depth {
+                        visited.insert(object.clone());
+                        to_describe.push_back((object.clone(), depth + 1));
+                    }
+                }
+            }
+        }
+
+        (member, validator.violations())
+    }
+}
+
+impl MemberExtraction for ConciseBoundedDescriptionExtraction {
+    fn add_triple(
+        &mut self,
+        triple: &rio_api::model::Triple,
+        graph_name: Option<&rio_api::model::GraphName>,
+    ) -> Result<Vec<Member>, Error> {
+        if let Some(id) = member_id(triple, &self.re_member_id) {
+            if self.member_id_set.insert(id.clone()) {
+                self.member_ids.push(id);
+            }
+        }
+        self.triples_by_subject
+            .entry(triple.subject.to_string())
+            .or_default()
+            .push(MemberTriple::new(
+                triple,
+                graph_name,
+                &self.date_field,
+                &self.date_formats,
+            )?);
+        Ok(Vec::new())
+    }
+
+    fn finalize(&mut self) -> Vec<Member> {
+        let mut resp = Vec::with_capacity(self.member_ids.len());
+        for id in self.member_ids.iter() {
+            let (member, violations) = self.describe(id);
+            if violations.is_empty() {
+                self.data_quality.add_valid_member();
+                resp.push(member);
+            } else {
+                self.data_quality.add_invalid_member(&violations);
+                self.rejected_members
+                    .push(RejectedMember { member, violations });
+            }
+        }
+        self.triples_by_subject.clear();
+        resp
+    }
+
+    fn data_quality(&self) -> &DataQualityReport {
+        &self.data_quality
+    }
+
+    fn take_rejected_members(&mut self) -> Vec<RejectedMember> {
+        mem::take(&mut self.rejected_members)
+    }
+}
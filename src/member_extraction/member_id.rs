@@ -0,0 +1,332 @@
+use crate::config::{is_same_iri, Config, MemberIdSource};
+use crate::error::Error;
+use crate::member::Member;
+use regex;
+
+/// Give their id to the members following the [MemberIdSource] of the [Config],
+/// a member without id cannot be published in the collection
+pub struct MemberIdentifier {
+    source: MemberIdSource,
+    re_member_id: regex::Regex,
+    server_address: String,
+    n_members: usize,
+}
+
+impl MemberIdentifier {
+    pub fn new(config: &Config) -> Result<Self, Error> {
+        let re_member_id = super::member_url_regex(config)?;
+        match &config.member_id {
+            MemberIdSource::RegexCaptureGroup { group }
+                if *group >= re_member_id.captures_len() =>
+            {
+                return Err(Error::Config(format!(
+                    "the member url regex {} has no capture group {group}",
+                    config.member_url_regex
+                )));
+            }
+            MemberIdSource::Template { template } if !template.contains("{n}") => {
+                return Err(Error::Config(format!(
+                    "the member id template {template} should contain {{n}} so that the ids are unique"
+                )));
+            }
+            _ => {}
+        }
+        Ok(Self {
+            source: config.member_id.clone(),
+            re_member_id,
+            server_address: config.server_address.clone(),
+            n_members: 0,
+        })
+    }
+
+    /// Give its id to a member, the triples of the member are rewritten with its id so that
+    /// it describes the member of the collection. The violation is returned when the member does
+    /// not have the information of its id
+    pub fn identify(&mut self, member: &mut Member) -> Result<(), String> {
+        self.n_members += 1;
+        let id = match &self.source {
+            MemberIdSource::Subject => (!member.id.is_empty()).then(|| member.id.clone()),
+            MemberIdSource::RegexCaptureGroup { group } => self
+                .re_member_id
+                .captures(member.id.trim_start_matches('<').trim_end_matches('>'))
+                .and_then(|captures| captures.get(*group))
+                .map(|capture| self.resolve(capture.as_str())),
+            MemberIdSource::Predicate { predicate } => {
+                Self::object_of(member, predicate).and_then(|object| self.term_to_iri(object))
+            }
+            MemberIdSource::GraphName => {
+                member.graph.clone().filter(|graph| graph.starts_with('<'))
+            }
+            MemberIdSource::Template { template } => Some(format!(
+                "<{}>",
+                template
+                    .replace("{server}", self.server_address.trim_end_matches('/'))
+                    .replace("{n}", &self.n_members.to_string())
+            )),
+        };
+        match id {
+            Some(id) => {
+                // the named graph is the member, its triples keep their subject
+                if !matches!(
+                    self.source,
+                    MemberIdSource::Subject | MemberIdSource::GraphName
+                ) {
+                    Self::rename_subject(member, &id);
+                }
+                member.id = id;
+                Ok(())
+            }
+            None => Err(match &self.source {
+                MemberIdSource::RegexCaptureGroup { group } => {
+                    format!("the capture group {group} of the member url regex does not match the member")
+                }
+                MemberIdSource::Predicate { predicate } => {
+                    format!("the member has no IRI or literal object of {predicate} to be its id")
+                }
+                MemberIdSource::GraphName => "the member is not in a named graph".to_string(),
+                _ => "the member has no subject respecting the member url regex".to_string(),
+            }),
+        }
+    }
+
+    /// Replace the subject of the member by its id in its triples
+    fn rename_subject(member: &mut Member, id: &str) {
+        if member.id.is_empty() || member.id == id {
+            return;
+        }
+        let subject = format!("{} ", member.id);
+        let object = format!(" {} .", member.id);
+        for property in member.properties.iter_mut() {
+            if let Some(rest) = property.strip_prefix(&subject) {
+                *property = format!("{id} {rest}");
+            }
+            if let Some(rest) = property.strip_suffix(&object) {
+                *property = format!("{rest} {id} .");
+            }
+        }
+    }
+
+    /// The object of the first triple of the member with the predicate, the triples having
+    /// the member as subject come first
+    fn object_of<'a>(member: &'a Member, predicate: &str) -> Option<&'a str> {
+        // the properties are N-Triples statements, the subject and the predicate do not contain spaces
+        let triples = member.properties.iter().filter_map(|property| {
+            let mut terms = property.splitn(3, ' ');
+            let subject = terms.next()?;
+            let triple_predicate = terms.next()?;
+            let object = terms.next()?.strip_suffix(" .")?;
+            is_same_iri(predicate, triple_predicate).then_some((subject, object))
+        });
+        let (member_triples, other_triples): (Vec<_>, Vec<_>) =
+            triples.partition(|(subject, _)| *subject == member.id);
+        member_triples
+            .into_iter()
+            .chain(other_triples)
+            .map(|(_, object)| object)
+            .next()
+    }
+
+    /// The IRI of a named node or of the value of a literal, the blank nodes cannot be an id
+    fn term_to_iri(&self, term: &str) -> Option<String> {
+        if term.starts_with('<') {
+            Some(term.to_string())
+        } else if let Some(literal) = term.strip_prefix('"') {
+            let value = &literal[..literal.rfind('"')?];
+            (!value.is_empty()).then(|| self.resolve(value))
+        } else {
+            None
+        }
+    }
+
+    /// Resolve a relative IRI against the server address, the characters that cannot be part
+    /// of an IRI are percent-encoded
+    fn resolve(&self, iri: &str) -> String {
+        let has_scheme = iri.split_once(':').is_some_and(|(scheme, _)| {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        });
+        let mut resp = if has_scheme {
+            String::from("<")
+        } else {
+            format!("<{}", self.server_address)
+        };
+        let iri = if has_scheme {
+            iri
+        } else {
+            iri.trim_start_matches('/')
+        };
+        for c in iri.chars() {
+            if c.is_whitespace() || c.is_control() || "<>\"{}|^`\\".contains(c) {
+                let mut buffer = [0u8; 4];
+                for byte in c.encode_utf8(&mut buffer).bytes() {
+                    resp.push_str(&format!("%{byte:02X}"));
+                }
+            } else {
+                resp.push(c);
+            }
+        }
+        resp.push('>');
+        resp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identifier(source: MemberIdSource) -> MemberIdentifier {
+        MemberIdentifier {
+            source,
+            re_member_id: regex::Regex::new("http://ex.org/obs/(\\d+)").unwrap(),
+            server_address: "http://localhost:8080/".to_string(),
+            n_members: 0,
+        }
+    }
+
+    fn member(id: &str, properties: &[&str]) -> Member {
+        Member {
+            id: id.to_string(),
+            properties: properties
+                .iter()
+                .map(|property| property.to_string())
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn resolve_keeps_the_absolute_iris() {
+        let identifier = identifier(MemberIdSource::Subject);
+        assert_eq!(identifier.resolve("urn:uuid:1234"), "<urn:uuid:1234>");
+        assert_eq!(identifier.resolve("http://ex.org/a"), "<http://ex.org/a>");
+    }
+
+    #[test]
+    fn resolve_prefixes_the_relative_iris_with_the_server_address() {
+        let identifier = identifier(MemberIdSource::Subject);
+        assert_eq!(identifier.resolve("obs/1"), "<http://localhost:8080/obs/1>");
+        assert_eq!(
+            identifier.resolve("/obs/1"),
+            "<http://localhost:8080/obs/1>"
+        );
+        // a colon does not make a scheme when it is preceded by an invalid character
+        assert_eq!(identifier.resolve("1:2"), "<http://localhost:8080/1:2>");
+    }
+
+    #[test]
+    fn resolve_percent_encodes_the_invalid_characters() {
+        let identifier = identifier(MemberIdSource::Subject);
+        assert_eq!(
+            identifier.resolve("a b<c>é"),
+            "<http://localhost:8080/a%20b%3Cc%3Eé>"
+        );
+    }
+
+    #[test]
+    fn term_to_iri_accepts_the_iris_and_the_literals() {
+        let identifier = identifier(MemberIdSource::Subject);
+        assert_eq!(
+            identifier.term_to_iri("<http://ex.org/a>"),
+            Some("<http://ex.org/a>".to_string())
+        );
+        assert_eq!(
+            identifier.term_to_iri("\"obs 1\"@en"),
+            Some("<http://localhost:8080/obs%201>".to_string())
+        );
+        assert_eq!(
+            identifier.term_to_iri("\"42\"^^<http://www.w3.org/2001/XMLSchema#integer>"),
+            Some("<http://localhost:8080/42>".to_string())
+        );
+        assert_eq!(identifier.term_to_iri("\"\""), None);
+        assert_eq!(identifier.term_to_iri("_:b0"), None);
+    }
+
+    #[test]
+    fn identify_with_a_capture_group() {
+        let mut identifier = identifier(MemberIdSource::RegexCaptureGroup { group: 1 });
+        let mut member = member("<http://ex.org/obs/12>", &[]);
+        identifier.identify(&mut member).unwrap();
+        assert_eq!(member.id, "<http://localhost:8080/12>");
+        assert!(identifier
+            .identify(&mut self::member("<http://ex.org/other>", &[]))
+            .is_err());
+    }
+
+    #[test]
+    fn identify_with_a_predicate_prefers_the_triples_of_the_member() {
+        let mut identifier = identifier(MemberIdSource::Predicate {
+            predicate: "http://ex.org/id".to_string(),
+        });
+        let mut member = member(
+            "<http://ex.org/obs/1>",
+            &[
+                "_:b0 <http://ex.org/id> \"other\" .",
+                "<http://ex.org/obs/1> <http://ex.org/id> <http://ex.org/id/1> .",
+            ],
+        );
+        identifier.identify(&mut member).unwrap();
+        assert_eq!(member.id, "<http://ex.org/id/1>");
+    }
+
+    #[test]
+    fn identify_with_a_template_numbers_the_members() {
+        let mut identifier = identifier(MemberIdSource::Template {
+            template: "{server}/members/{n}".to_string(),
+        });
+        let mut first = member("", &[]);
+        let mut second = member("", &[]);
+        identifier.identify(&mut first).unwrap();
+        identifier.identify(&mut second).unwrap();
+        assert_eq!(first.id, "<http://localhost:8080/members/1>");
+        assert_eq!(second.id, "<http://localhost:8080/members/2>");
+    }
+
+    #[test]
+    fn identify_rewrites_the_triples_of_the_member_with_its_id() {
+        let mut identifier = identifier(MemberIdSource::Template {
+            template: "{server}/members/{n}".to_string(),
+        });
+        let mut member = member(
+            "<http://ex.org/obs/1>",
+            &[
+                "<http://ex.org/obs/1> <http://ex.org/p> \"a\" .",
+                "<http://ex.org/obs/1> <http://ex.org/q> _:b0 .",
+                "_:b0 <http://ex.org/of> <http://ex.org/obs/1> .",
+                "<http://ex.org/obs/10> <http://ex.org/p> <http://ex.org/obs/1/x> .",
+            ],
+        );
+        identifier.identify(&mut member).unwrap();
+        assert_eq!(
+            member.properties,
+            vec![
+                "<http://localhost:8080/members/1> <http://ex.org/p> \"a\" .",
+                "<http://localhost:8080/members/1> <http://ex.org/q> _:b0 .",
+                "_:b0 <http://ex.org/of> <http://localhost:8080/members/1> .",
+                "<http://ex.org/obs/10> <http://ex.org/p> <http://ex.org/obs/1/x> .",
+            ]
+        );
+    }
+
+    #[test]
+    fn identify_with_the_subject_or_the_graph_keeps_the_triples() {
+        let triples = ["<http://ex.org/obs/1> <http://ex.org/p> \"a\" ."];
+        let mut identifier = identifier(MemberIdSource::GraphName);
+        let mut member = member("<http://ex.org/obs/1>", &triples);
+        member.graph = Some("<http://ex.org/graph/1>".to_string());
+        identifier.identify(&mut member).unwrap();
+        assert_eq!(member.id, "<http://ex.org/graph/1>");
+        assert_eq!(member.properties, triples);
+    }
+
+    #[test]
+    fn identify_without_information_returns_the_violation() {
+        let mut identifier = identifier(MemberIdSource::GraphName);
+        let mut member = member("<http://ex.org/obs/1>", &[]);
+        assert_eq!(
+            identifier.identify(&mut member),
+            Err("the member is not in a named graph".to_string())
+        );
+    }
+}
@@ -0,0 +1,431 @@
+pub mod concise_bounded_description;
+pub mod member_id;
+pub mod named_graph;
+pub mod schema_complete;
+pub mod subject_grouped;
+
+use self::concise_bounded_description::ConciseBoundedDescriptionExtraction;
+use self::named_graph::NamedGraphExtraction;
+use self::schema_complete::SchemaCompleteExtraction;
+use self::subject_grouped::SubjectGroupedExtraction;
+use crate::config::*;
+use crate::date::{parse_date, to_time_axis};
+use crate::error::Error;
+use crate::fragmentation::report::DataQualityReport;
+use crate::member::Member;
+use crate::shape::{NodeShape, TYPE_VOCAB};
+use std::collections::{HashMap, HashSet};
+
+/// Define the boundaries of the members inside the stream of triples of the data dump
+pub trait MemberExtraction {
+    /// Add the next triple of the data dump and the named graph it belongs to,
+    /// return the members completed by it
+    fn add_triple(
+        &mut self,
+        triple: &rio_api::model::Triple,
+        graph_name: Option<&rio_api::model::GraphName>,
+    ) -> Result<Vec<Member>, Error>;
+    /// Return the members still under construction at the end of the data dump
+    fn finalize(&mut self) -> Vec<Member>;
+    /// The number of valid members and the violations of the rejected ones
+    fn data_quality(&self) -> &DataQualityReport;
+    /// Take the members rejected since the last call
+    fn take_rejected_members(&mut self) -> Vec<RejectedMember>;
+}
+
+/// A member that does not respect the schema or the shape, that has no date or no id
+#[derive(Debug, Clone)]
+pub struct RejectedMember {
+    pub member: Member,
+    /// The reasons of the rejection
+    pub violations: Vec<String>,
+}
+
+/// A triple of the data dump in the form used to build a member
+#[derive(Clone)]
+pub struct MemberTriple {
+    /// The triple serialized as a property of the member
+    pub property: String,
+    /// The date of the member on the time axis and the priority of its date field
+    /// if the predicate of the triple is a date field
+    pub date: Option<(usize, i64)>,
+    /// The reason why the object of the date field is not a date,
+    /// the member is only rejected when none of its date fields is a date
+    pub date_violation: Option<String>,
+    /// The object of the triple if it is a node that can be the subject of other triples
+    pub linked_object: Option<String>,
+    /// The named graph of the triple, [None] for the default graph
+    pub graph: Option<String>,
+    pub validator_input: SchemaValidatorInput,
+}
+
+impl MemberTriple {
+    pub fn new(
+        t: &rio_api::model::Triple,
+        graph_name: Option<&rio_api::model::GraphName>,
+        date_field: &[String],
+        date_formats: &Vec<String>,
+    ) -> Result<Self, Error> {
+        // we add the date into a specific field because it is the bases of the fragmentation
+        let date_priority = date_field
+            .iter()
+            .position(|date_field| is_same_iri(date_field, t.predicate.iri));
+        let (date, date_violation) = if let Some(date_priority) = date_priority {
+            if let rio_api::model::Term::Literal(literal) = t.object {
+                match parse_date(&literal, date_formats)
+                    .and_then(|date| to_time_axis(&date.naive_utc()))
+                {
+                    Ok(date) => (Some((date_priority, date)), None),
+                    Err(_) => (
+                        None,
+                        Some(format!("the date of {} cannot be parsed", t.predicate)),
+                    ),
+                }
+            } else {
+                (
+                    None,
+                    Some(format!("the date of {} is not a literal", t.predicate)),
+                )
+            }
+        } else {
+            (None, None)
+        };
+
+        let linked_object = match t.object {
+            rio_api::model::Term::NamedNode(_) | rio_api::model::Term::BlankNode(_) => {
+                Some(t.object.to_string())
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            property: format!("{t} ."),
+            date,
+            date_violation,
+            linked_object,
+            graph: graph_name.map(|graph_name| graph_name.to_string()),
+            validator_input: SchemaValidatorInput::from_triple(t),
+        })
+    }
+
+    /// Add the triple as a property of the member, its date replaces the date of the member
+    /// if its date field has a higher priority
+    pub fn push_to(&self, member: &mut Member) {
+        member.properties.push(self.property.clone());
+        if let Some((date_priority, date)) = self.date {
+            if member
+                .date_priority
+                .is_none_or(|priority| date_priority <= priority)
+            {
+                member.date = date;
+                member.date_priority = Some(date_priority);
+            }
+        }
+        if self.graph.is_some() {
+            member.graph = self.graph.clone();
+        }
+    }
+}
+
+/// The id of the member if the subject of the triple respects the member IRI regex
+pub(super) fn member_id(t: &rio_api::model::Triple, re_member_id: &regex::Regex) -> Option<String> {
+    if let rio_api::model::Subject::NamedNode(subject) = t.subject {
+        if re_member_id.is_match(subject.iri) {
+            return Some(t.subject.to_string());
+        }
+    }
+    None
+}
+
+/// Follow the validity of the triples of a member with the [Schema]s and the [NodeShape]
+pub struct MemberValidator {
+    schema: Vec<Schema>,
+    valid_properties: Vec<bool>,
+    /// the labels of the linked subjects of the member
+    related_subjects: HashMap<String, HashSet<String>>,
+    /// the triples of the member that could not be attributed to a subject yet
+    pending_triples: Vec<SchemaValidatorInput>,
+    shape: Option<NodeShape>,
+    /// the number of values of each property shape
+    shape_counts: Vec<usize>,
+    is_instance_of_target_class: bool,
+    /// the violations of single triples of the member
+    triple_violations: Vec<String>,
+    /// a date field of the member has a valid date
+    has_date: bool,
+    /// the date fields whose object is not a date, they only matter when the member has no date
+    date_violations: Vec<String>,
+}
+
+impl MemberValidator {
+    pub fn new(schema: Vec<Schema>, shape: Option<NodeShape>) -> Self {
+        let valid_properties = vec![false; schema.len()];
+        let shape_counts = vec![0; shape.as_ref().map_or(0, |shape| shape.properties.len())];
+        Self {
+            schema,
+            valid_properties,
+            related_subjects: HashMap::new(),
+            pending_triples: Vec::new(),
+            shape,
+            shape_counts,
+            is_instance_of_target_class: false,
+            triple_violations: Vec::new(),
+            has_date: false,
+            date_violations: Vec::new(),
+        }
+    }
+
+    /// Validate a triple of the member. When the triple declares a linked subject
+    /// its object is labelled and the previous triples of the member describing it are validated again,
+    /// because the triples of a nested resource can be parsed before the triple linking it to the member.
+    pub fn validate(&mut self, member_triple: &MemberTriple, member_id: &String) {
+        let input = member_triple.validator_input.clone();
+        if member_triple.date.is_some() {
+            self.has_date = true;
+        }
+        if let Some(date_violation) = &member_triple.date_violation {
+            self.date_violations.push(date_violation.clone());
+        }
+        if input.subject == *member_id {
+            self.validate_shape(&input);
+        }
+        let mut to_validate = vec![input];
+        while let Some(mut input) = to_validate.pop() {
+            input.member_id = member_id.clone();
+            input.related_subject = self
+                .related_subjects
+                .get(&input.subject)
+                .cloned()
+                .unwrap_or_default();
+            for (i, schema) in self.schema.iter().enumerate() {
+                if !schema.is_valid(&input) {
+                    continue;
+                }
+                self.valid_properties[i] = true;
+                if let Some(label) = schema.linked_subject_label() {
+                    let is_new_label = self
+                        .related_subjects
+                        .entry(input.object.clone())
+                        .or_default()
+                        .insert(label.clone());
+                    if is_new_label {
+                        let (linked_triples, other_triples): (Vec<_>, Vec<_>) = self
+                            .pending_triples
+                            .drain(..)
+                            .partition(|pending| pending.subject == input.object);
+                        self.pending_triples = other_triples;
+                        to_validate.extend(linked_triples);
+                    }
+                }
+            }
+            if input.subject != input.member_id {
+                self.pending_triples.push(input);
+            }
+        }
+    }
+
+    /// Validate a triple having the member as subject with the node shape
+    fn validate_shape(&mut self, input: &SchemaValidatorInput) {
+        if let Some(shape) = &self.shape {
+            let predicate = input
+                .predicate
+                .trim_start_matches('<')
+                .trim_end_matches('>');
+            if predicate == TYPE_VOCAB && shape.target_class.as_ref() == Some(&input.object_value) {
+                self.is_instance_of_target_class = true;
+            }
+            for (i, property) in shape.properties.iter().enumerate() {
+                if property.path != predicate {
+                    continue;
+                }
+                self.shape_counts[i] += 1;
+                if let Some(datatype) = &property.datatype {
+                    if input.object_datatype.as_ref() != Some(datatype) {
+                        self.triple_violations.push(format!(
+                            "the datatype of <{}> is not <{}>",
+                            property.path, datatype
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Every [Schema] has been respected by a triple of the member,
+    /// the member is an instance of the target class of the node shape
+    /// and the minimal cardinality of every property shape is reached
+    pub fn is_complete(&self) -> bool {
+        let is_shape_complete = match &self.shape {
+            Some(shape) => {
+                (shape.target_class.is_none() || self.is_instance_of_target_class)
+                    && shape
+                        .properties
+                        .iter()
+                        .zip(self.shape_counts.iter())
+                        .all(|(property, count)| *count >= property.min_count.unwrap_or(0))
+            }
+            None => true,
+        };
+        self.valid_properties.iter().all(|valid| *valid) && is_shape_complete
+    }
+
+    /// The reasons why the member is not valid, it is empty if the member is valid
+    pub fn violations(&self) -> Vec<String> {
+        let mut resp = Vec::new();
+        for (schema, valid) in self.schema.iter().zip(self.valid_properties.iter()) {
+            if !valid {
+                resp.push(format!(
+                    "no triple respecting the schema of {}",
+                    schema.predicate
+                ));
+            }
+        }
+        if let Some(shape) = &self.shape {
+            if let Some(target_class) = &shape.target_class {
+                if !self.is_instance_of_target_class {
+                    resp.push(format!("not an instance of <{target_class}>"));
+                }
+            }
+            for (property, count) in shape.properties.iter().zip(self.shape_counts.iter()) {
+                if *count < property.min_count.unwrap_or(0) {
+                    resp.push(format!(
+                        "less than sh:minCount values of <{}>",
+                        property.path
+                    ));
+                }
+                if *count > property.max_count.unwrap_or(usize::MAX) {
+                    resp.push(format!(
+                        "more than sh:maxCount values of <{}>",
+                        property.path
+                    ));
+                }
+            }
+        }
+        resp.extend(self.triple_violations.iter().cloned());
+        if !self.has_date {
+            if self.date_violations.is_empty() {
+                resp.push("no date in any of the date fields".to_string());
+            } else {
+                resp.extend(self.date_violations.iter().cloned());
+            }
+        }
+        resp
+    }
+
+    pub fn clear(&mut self) {
+        self.valid_properties = vec![false; self.schema.len()];
+        self.related_subjects.clear();
+        self.pending_triples.clear();
+        self.shape_counts = vec![0; self.shape_counts.len()];
+        self.is_instance_of_target_class = false;
+        self.triple_violations.clear();
+        self.has_date = false;
+        self.date_violations.clear();
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum MemberExtractionTypeName {
+    SchemaComplete,
+    SubjectGrouped,
+    ConciseBoundedDescription,
+    NamedGraph,
+}
+
+impl TryFrom<String> for MemberExtractionTypeName {
+    type Error = Error;
+
+    fn try_from(item: String) -> Result<Self, Self::Error> {
+        if item == "schemaComplete" {
+            Ok(Self::SchemaComplete)
+        } else if item == "subjectGrouped" {
+            Ok(Self::SubjectGrouped)
+        } else if item == "cbd" {
+            Ok(Self::ConciseBoundedDescription)
+        } else if item == "namedGraph" {
+            Ok(Self::NamedGraph)
+        } else {
+            Err(Error::Config(format!(
+                "member extraction {item} not supported"
+            )))
+        }
+    }
+}
+
+/// The regex the IRI of the members must respect
+fn member_url_regex(config: &Config) -> Result<regex::Regex, Error> {
+    regex::Regex::new(&config.member_url_regex).map_err(|e| {
+        Error::Config(format!(
+            "the member url regex {} is not valid: {e}",
+            config.member_url_regex
+        ))
+    })
+}
+
+pub fn factory(
+    member_extraction_type: MemberExtractionTypeName,
+    config: &Config,
+    shape: Option<NodeShape>,
+) -> Result<Box<dyn MemberExtraction>, Error> {
+    Ok(match member_extraction_type {
+        MemberExtractionTypeName::SchemaComplete => {
+            Box::new(SchemaCompleteExtraction::new(config, shape)?)
+        }
+        MemberExtractionTypeName::SubjectGrouped => {
+            Box::new(SubjectGroupedExtraction::new(config, shape)?)
+        }
+        MemberExtractionTypeName::ConciseBoundedDescription => {
+            Box::new(ConciseBoundedDescriptionExtraction::new(config, shape)?)
+        }
+        MemberExtractionTypeName::NamedGraph => Box::new(NamedGraphExtraction::new(config, shape)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::PropertyShape;
+    use rio_api::model::{NamedNode, Triple};
+
+    const MEMBER: &str = "http://ex.org/obs/1";
+
+    fn validate(validator: &mut MemberValidator, predicate: &str, object: &str) {
+        let triple = Triple {
+            subject: NamedNode { iri: MEMBER }.into(),
+            predicate: NamedNode { iri: predicate },
+            object: NamedNode { iri: object }.into(),
+        };
+        let member_triple = MemberTriple::new(&triple, None, &[], &Vec::new()).unwrap();
+        validator.validate(&member_triple, &format!("<{MEMBER}>"));
+    }
+
+    fn shape(target_class: Option<&str>) -> NodeShape {
+        NodeShape {
+            iri: None,
+            target_class: target_class.map(|target_class| target_class.to_string()),
+            properties: vec![PropertyShape {
+                path: "http://ex.org/p".to_string(),
+                min_count: Some(1),
+                max_count: None,
+                datatype: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn a_member_is_complete_once_it_is_an_instance_of_the_target_class() {
+        let mut validator = MemberValidator::new(Vec::new(), Some(shape(Some("http://ex.org/C"))));
+        validate(&mut validator, "http://ex.org/p", "http://ex.org/o");
+        assert!(!validator.is_complete());
+        validate(&mut validator, TYPE_VOCAB, "http://ex.org/C");
+        assert!(validator.is_complete());
+    }
+
+    #[test]
+    fn a_member_without_target_class_is_complete_with_its_min_counts() {
+        let mut validator = MemberValidator::new(Vec::new(), Some(shape(None)));
+        assert!(!validator.is_complete());
+        validate(&mut validator, "http://ex.org/p", "http://ex.org/o");
+        assert!(validator.is_complete());
+    }
+}
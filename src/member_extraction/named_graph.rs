@@ -0,0 +1,121 @@
+use super::*;
+use crate::config::{is_same_iri, Config};
+use crate::error::Error;
+use crate::member::Member;
+use std::mem;
+
+/// Each named graph is a member, the name of the graph is the id of the member unless
+/// a member predicate is configured. The triples of a named graph are expected to be contiguous
+/// in the data dump and the triples of the default graph are not part of a member.
+/// The [Schema]s and the [NodeShape] only validate the members.
+pub struct NamedGraphExtraction {
+    /// the named graph of the current member
+    current_graph: Option<String>,
+    /// the triples of the current named graph, they are validated once the id of the member is known
+    current_triples: Vec<MemberTriple>,
+    /// the id found with the member predicate
+    current_id: Option<String>,
+    member_predicate: Option<String>,
+    validator: MemberValidator,
+    date_field: Vec<String>,
+    date_formats: Vec<String>,
+    n_properties: usize,
+    data_quality: DataQualityReport,
+    rejected_members: Vec<RejectedMember>,
+}
+
+impl NamedGraphExtraction {
+    pub fn new(config: &Config, shape: Option<NodeShape>) -> Self {
+        Self {
+            current_graph: None,
+            current_triples: Vec::new(),
+            current_id: None,
+            member_predicate: config.named_graph_member_predicate.clone(),
+            validator: MemberValidator::new(config.schema.clone(), shape),
+            date_field: config.date_field.clone(),
+            date_formats: config.date_formats.clone(),
+            n_properties: config.schema.len(),
+            data_quality: DataQualityReport::default(),
+            rejected_members: Vec::new(),
+        }
+    }
+
+    /// Close the member of the current named graph, it is returned if it respects the schema and the shape
+    fn close_member(&mut self) -> Option<Member> {
+        let graph = self.current_graph.take()?;
+        let triples = mem::take(&mut self.current_triples);
+        let id = match (&self.member_predicate, self.current_id.take()) {
+            (None, _) => Some(graph),
+            (Some(_), id) => id,
+        };
+
+        let mut member = Member::new(self.n_properties);
+        member.id = id.clone().unwrap_or_default();
+        for member_triple in triples {
+            member_triple.push_to(&mut member);
+            self.validator.validate(&member_triple, &member.id);
+        }
+        let violations = match (&self.member_predicate, id) {
+            (Some(member_predicate), None) => vec![format!(
+                "no triple with the member predicate {member_predicate}"
+            )],
+            _ => self.validator.violations(),
+        };
+        self.validator.clear();
+
+        if violations.is_empty() {
+            self.data_quality.add_valid_member();
+            Some(member)
+        } else {
+            self.data_quality.add_invalid_member(&violations);
+            self.rejected_members
+                .push(RejectedMember { member, violations });
+            None
+        }
+    }
+}
+
+impl MemberExtraction for NamedGraphExtraction {
+    fn add_triple(
+        &mut self,
+        triple: &rio_api::model::Triple,
+        graph_name: Option<&rio_api::model::GraphName>,
+    ) -> Result<Vec<Member>, Error> {
+        let member_triple =
+            MemberTriple::new(triple, graph_name, &self.date_field, &self.date_formats)?;
+        let mut resp = Vec::new();
+        if member_triple.graph.is_none() {
+            return Ok(resp);
+        }
+
+        if member_triple.graph != self.current_graph {
+            if let Some(member) = self.close_member() {
+                resp.push(member);
+            }
+            self.current_graph = member_triple.graph.clone();
+        }
+
+        if let Some(member_predicate) = &self.member_predicate {
+            if self.current_id.is_none()
+                && is_same_iri(member_predicate, &member_triple.validator_input.predicate)
+            {
+                self.current_id = Some(member_triple.validator_input.subject.clone());
+            }
+        }
+        self.current_triples.push(member_triple);
+
+        Ok(resp)
+    }
+
+    fn finalize(&mut self) -> Vec<Member> {
+        self.close_member().into_iter().collect()
+    }
+
+    fn data_quality(&self) -> &DataQualityReport {
+        &self.data_quality
+    }
+
+    fn take_rejected_members(&mut self) -> Vec<RejectedMember> {
+        mem::take(&mut self.rejected_members)
+    }
+}
@@ -0,0 +1,103 @@
+use super::*;
+use crate::config::Config;
+use crate::error::Error;
+use crate::member::Member;
+use regex;
+use std::mem;
+
+/// A member is complete as soon as each [Schema] has been respected by one of its triples,
+/// its type is the target class of the [NodeShape] and the minimal cardinalities of the shape are reached,
+/// the first subject respecting the member IRI regex is the id of the member.
+pub struct SchemaCompleteExtraction {
+    current_member: Member,
+    validator: MemberValidator,
+    re_member_id: regex::Regex,
+    date_field: Vec<String>,
+    date_formats: Vec<String>,
+    n_properties: usize,
+    data_quality: DataQualityReport,
+    rejected_members: Vec<RejectedMember>,
+}
+
+impl SchemaCompleteExtraction {
+    pub fn new(config: &Config, shape: Option<NodeShape>) -> Result<Self, Error> {
+        Ok(Self {
+            current_member: Member::default(),
+            validator: MemberValidator::new(config.schema.clone(), shape),
+            re_member_id: member_url_regex(config)?,
+            date_field: config.date_field.clone(),
+            date_formats: config.date_formats.clone(),
+            n_properties: config.schema.len(),
+            data_quality: DataQualityReport::default(),
+            rejected_members: Vec::new(),
+        })
+    }
+
+    /// Reject the current member because it is not complete
+    fn reject_current_member(&mut self) {
+        let violations = self.validator.violations();
+        self.validator.clear();
+        let member = mem::replace(&mut self.current_member, Member::new(self.n_properties));
+        self.data_quality.add_invalid_member(&violations);
+        self.rejected_members
+            .push(RejectedMember { member, violations });
+    }
+}
+
+impl MemberExtraction for SchemaCompleteExtraction {
+    fn add_triple(
+        &mut self,
+        triple: &rio_api::model::Triple,
+        graph_name: Option<&rio_api::model::GraphName>,
+    ) -> Result<Vec<Member>, Error> {
+        // the triples of nested blank nodes can be emitted before the member IRI
+        if let Some(id) = member_id(triple, &self.re_member_id) {
+            if self.current_member.id.is_empty() {
+                self.current_member.id = id;
+            } else if id != self.current_member.id {
+                // the current member never became complete and the next member starts
+                self.reject_current_member();
+                self.current_member.id = id;
+            }
+        }
+
+        let member_triple =
+            MemberTriple::new(triple, graph_name, &self.date_field, &self.date_formats)?;
+        member_triple.push_to(&mut self.current_member);
+        self.validator
+            .validate(&member_triple, &self.current_member.id);
+
+        // the current member is materialized if it is complete
+        if self.validator.is_complete() {
+            let violations = self.validator.violations();
+            self.validator.clear();
+            let member = mem::replace(&mut self.current_member, Member::new(self.n_properties));
+            if violations.is_empty() {
+                self.data_quality.add_valid_member();
+                Ok(vec![member])
+            } else {
+                self.data_quality.add_invalid_member(&violations);
+                self.rejected_members
+                    .push(RejectedMember { member, violations });
+                Ok(Vec::new())
+            }
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    fn finalize(&mut self) -> Vec<Member> {
+        if !self.current_member.properties.is_empty() {
+            self.reject_current_member();
+        }
+        Vec::new()
+    }
+
+    fn data_quality(&self) -> &DataQualityReport {
+        &self.data_quality
+    }
+
+    fn take_rejected_members(&mut self) -> Vec<RejectedMember> {
+        mem::take(&mut self.rejected_members)
+    }
+}
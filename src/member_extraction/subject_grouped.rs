@@ -0,0 +1,147 @@
+use super::*;
+use crate::config::Config;
+use crate::error::Error;
+use crate::member::Member;
+use regex;
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+/// A member groups the triples of its subject and of the resources linked to it,
+/// it is complete when a triple with another member subject or from another named graph is parsed
+/// or at the end of the data dump.
+/// The [Schema]s and the [NodeShape] only validate the members.
+pub struct SubjectGroupedExtraction {
+    current_member: Member,
+    validator: MemberValidator,
+    /// the objects of the triples of the current member
+    linked_subjects: HashSet<String>,
+    /// the triples of blank nodes parsed since the last member started and not yet linked to a member
+    pending_triples: HashMap<String, Vec<MemberTriple>>,
+    /// the triples of blank nodes parsed during the previous member, they can describe
+    /// a resource nested in the current member
+    previous_pending_triples: HashMap<String, Vec<MemberTriple>>,
+    /// the named graph of the current member
+    current_graph: Option<String>,
+    re_member_id: regex::Regex,
+    date_field: Vec<String>,
+    date_formats: Vec<String>,
+    n_properties: usize,
+    data_quality: DataQualityReport,
+    rejected_members: Vec<RejectedMember>,
+}
+
+impl SubjectGroupedExtraction {
+    pub fn new(config: &Config, shape: Option<NodeShape>) -> Result<Self, Error> {
+        Ok(Self {
+            current_member: Member::default(),
+            validator: MemberValidator::new(config.schema.clone(), shape),
+            linked_subjects: HashSet::new(),
+            pending_triples: HashMap::new(),
+            previous_pending_triples: HashMap::new(),
+            current_graph: None,
+            re_member_id: member_url_regex(config)?,
+            date_field: config.date_field.clone(),
+            date_formats: config.date_formats.clone(),
+            n_properties: config.schema.len(),
+            data_quality: DataQualityReport::default(),
+            rejected_members: Vec::new(),
+        })
+    }
+
+    /// Add the triple to the current member alongside the pending triples describing its object
+    fn attach(&mut self, member_triple: MemberTriple) {
+        let mut to_attach = vec![member_triple];
+        while let Some(member_triple) = to_attach.pop() {
+            if let Some(object) = &member_triple.linked_object {
+                if self.linked_subjects.insert(object.clone()) {
+                    for pending_triples in [
+                        &mut self.pending_triples,
+                        &mut self.previous_pending_triples,
+                    ] {
+                        if let Some(triples) = pending_triples.remove(object) {
+                            to_attach.extend(triples);
+                        }
+                    }
+                }
+            }
+            member_triple.push_to(&mut self.current_member);
+            self.validator
+                .validate(&member_triple, &self.current_member.id);
+        }
+    }
+
+    /// Close the current member, it is returned if it respects the schema and the shape
+    fn close_member(&mut self) -> Option<Member> {
+        let member = mem::replace(&mut self.current_member, Member::new(self.n_properties));
+        let violations = self.validator.violations();
+        self.validator.clear();
+        self.linked_subjects.clear();
+        // the blank nodes not linked during two members are not part of a member
+        self.previous_pending_triples = mem::take(&mut self.pending_triples);
+
+        if member.properties.is_empty() {
+            None
+        } else if violations.is_empty() {
+            self.data_quality.add_valid_member();
+            Some(member)
+        } else {
+            self.data_quality.add_invalid_member(&violations);
+            self.rejected_members
+                .push(RejectedMember { member, violations });
+            None
+        }
+    }
+}
+
+impl MemberExtraction for SubjectGroupedExtraction {
+    fn add_triple(
+        &mut self,
+        triple: &rio_api::model::Triple,
+        graph_name: Option<&rio_api::model::GraphName>,
+    ) -> Result<Vec<Member>, Error> {
+        let member_triple =
+            MemberTriple::new(triple, graph_name, &self.date_field, &self.date_formats)?;
+        let subject = triple.subject.to_string();
+        let mut resp = Vec::new();
+
+        // a named graph is a boundary of the members
+        if member_triple.graph != self.current_graph {
+            if let Some(member) = self.close_member() {
+                resp.push(member);
+            }
+            self.current_graph = member_triple.graph.clone();
+        }
+
+        if let Some(id) = member_id(triple, &self.re_member_id) {
+            if id != self.current_member.id {
+                if let Some(member) = self.close_member() {
+                    resp.push(member);
+                }
+                self.current_member.id = id;
+            }
+            self.attach(member_triple);
+        } else if self.linked_subjects.contains(&subject) {
+            self.attach(member_triple);
+        } else if let rio_api::model::Subject::BlankNode(_) = triple.subject {
+            self.pending_triples
+                .entry(subject)
+                .or_default()
+                .push(member_triple);
+        }
+        // otherwise the triple does not describe a member
+
+        Ok(resp)
+    }
+
+    fn finalize(&mut self) -> Vec<Member> {
+        self.close_member().into_iter().collect()
+    }
+
+    fn data_quality(&self) -> &DataQualityReport {
+        &self.data_quality
+    }
+
+    fn take_rejected_members(&mut self) -> Vec<RejectedMember> {
+        mem::take(&mut self.rejected_members)
+    }
+}
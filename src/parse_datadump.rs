@@ -1,145 +1,172 @@
+use super::compression::*;
 use super::config::*;
 use super::member::*;
+use crate::date::{from_time_axis, to_time_axis};
+use crate::error::Error;
+use crate::fragmentation::report::DataQualityReport;
 use crate::fragmentation::*;
-use chrono;
-use regex;
-use rio_api::parser::TriplesParser;
-use rio_turtle;
-use std::collections::HashSet;
+use crate::lenient::{parse_rdf_leniently, SyntaxErrorBudget};
+use crate::member_extraction::member_id::MemberIdentifier;
+use crate::member_extraction::{MemberExtractionTypeName, RejectedMember};
+use crate::shape::NodeShape;
+use crate::tree::{Collection, EventStream, OutputFormat};
+use rand::{Rng, SeedableRng};
+use rio_api::parser::{QuadsParser, TriplesParser};
 use std::collections::VecDeque;
-use std::error::Error;
-use std::fs::{read_to_string, File};
-use std::io::BufReader;
-use std::path::PathBuf;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Cursor, Read, Write};
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 use std::thread;
-use tokio;
+
+/// How the data dumps are read and their members extracted
+#[derive(Debug, Clone)]
+pub struct InputOptions {
+    pub data_dump_paths: Vec<PathBuf>,
+    /// The format of the data dumps, it is deduced from their extension when none
+    pub input_format: Option<InputFormat>,
+    pub large_file: bool,
+    /// The number of syntax errors tolerated by the lenient parsing, the parsing is strict when none
+    pub max_syntax_errors: Option<usize>,
+    pub member_extraction_type: MemberExtractionTypeName,
+}
 
 pub fn parse_datadump(
-    data_dump_path: PathBuf,
+    input: &InputOptions,
     data_injection_config: &Config,
     notice_frequency: usize,
-    large_file: bool,
-    max_cache_element: usize,
-    n_fragments_first_row: usize,
     out_path: PathBuf,
-    fragmentation_type: FragmentationTypeName,
-    dept: Option<usize>,
-) -> Result<(), Box<dyn Error>> {
-    let file = File::open(data_dump_path.clone())?;
-    let mut current_member = Member::default();
-    let n_properties = data_injection_config.schema.len();
-    let create_empy_valid_property = || -> Vec<bool> {
-        let mut resp = Vec::with_capacity(n_properties);
-        for _ in 0..n_properties {
-            resp.push(false);
-        }
-        resp
+    fragmentation_options: FragmentationOptions,
+    ldes: bool,
+    output_named_graphs: bool,
+) -> Result<(), Error> {
+    // the shape is published alongside the fragments
+    let shape = load_shape(data_injection_config)?;
+    if let Some(shape_path) = &data_injection_config.shape_path {
+        let mut published_shape_path = out_path.clone();
+        published_shape_path.push(SHAPE_FILENAME);
+        fs::copy(shape_path, &published_shape_path)
+            .map_err(|e| Error::io(&published_shape_path, e))?;
+    }
+    let mut member_extraction = crate::member_extraction::factory(
+        input.member_extraction_type.clone(),
+        data_injection_config,
+        shape.clone(),
+    )?;
+    let mut member_identifier = MemberIdentifier::new(data_injection_config)?;
+    let mut n_member_parsed = 0usize;
+    let n_members = data_injection_config.n_members.ok_or(Error::Config(
+        "the number of members should be defined in the config".to_string(),
+    ))?;
+
+    let output_format = if output_named_graphs {
+        OutputFormat::TriG
+    } else {
+        OutputFormat::Turtle
     };
-    let mut valid_properties = create_empy_valid_property();
-    let full_property_valid = {
-        let mut resp = Vec::with_capacity(n_properties);
-        for _ in 0..n_properties {
-            resp.push(true);
-        }
+    // the rejected members are written in a dead letter file so that the data dump can be audited
+    let dead_letter_path = {
+        let mut resp = out_path.clone();
+        resp.push(format!(
+            "{DEAD_LETTER_FILE_STEM}.{}",
+            output_format.extension()
+        ));
         resp
     };
-
-    let mut n_member_parsed = 0usize;
-    let re_member_id = regex::Regex::new(&data_injection_config.member_url_regex).unwrap();
+    let mut n_rejected_members = 0usize;
+    let mut reject_members = |rejected_members: Vec<RejectedMember>| -> Result<(), Error> {
+        n_rejected_members +=
+            write_rejected_members(&dead_letter_path, rejected_members, output_named_graphs)?;
+        Ok(())
+    };
 
     let (tx_member, rx_member) = mpsc::channel();
 
     let handle = tokio::runtime::Handle::current();
-    let parsing_function = &mut |t: rio_api::model::Triple| -> Result<(), Box<dyn Error>> {
-        // we give an id to the member we suppose that the first triple as has a subject the member IRI
-        if current_member.properties.len() == 0 {
-            let id = t.subject.to_string();
-            if re_member_id.is_match(&id) {
-                current_member.id = id;
+    // the members without id are rejected, the fragmentation fails once they are all written
+    let mut members_without_id_quality = DataQualityReport::default();
+    let mut send_members = |members: Vec<Member>| -> Result<Vec<RejectedMember>, Error> {
+        let mut members_without_id = Vec::new();
+        for mut member in members {
+            if let Err(violation) = member_identifier.identify(&mut member) {
+                members_without_id_quality.add_invalid_member(&vec![violation.clone()]);
+                members_without_id.push(RejectedMember {
+                    member,
+                    violations: vec![violation],
+                });
+                continue;
             }
-        }
-
-        // we add the triple as a property of the member
-        current_member
-            .properties
-            .push(format!("{} .", t.to_string()));
-
-        // we add the date into a specific field because it is the bases of the fragmentation
-        if t.predicate
-            .to_string()
-            .contains(&data_injection_config.date_field)
-        {
-            current_member.date = if let rio_api::model::Term::Literal(literal) = t.object {
-                if let rio_api::model::Literal::Typed { value, datatype: _ } = literal {
-                    chrono::NaiveDateTime::parse_from_str(
-                        &value.to_string(),
-                        "%Y-%m-%dT%H:%M:%S.%f",
-                    )?
-                    .timestamp()
-                } else {
-                    panic!("the date object is not typed '{:?}'", t.to_string());
-                }
-            } else {
-                panic!("the date object is not a literal '{:?}'", t.to_string());
-            };
-        }
-
-        // we check the property of the member if they match the schema
-        data_injection_config
-            .schema
-            .iter()
-            .enumerate()
-            .for_each(|(i, schema)| {
-                let input = SchemaValidatorInput {
-                    subject: t.subject.to_string(),
-                    predicate: t.predicate.to_string(),
-                    object: t.object.to_string(),
-                    member_id: current_member.id.clone(),
-                    related_subject: HashSet::new(),
-                };
-                if schema.is_valid(&input) {
-                    valid_properties[i] = true;
-                }
-            });
-
-        // the current member is materialized if it is complete
-        if valid_properties == full_property_valid {
-            tx_member.send(current_member.clone()).unwrap();
-            current_member = Member::new(n_properties);
-            valid_properties = create_empy_valid_property();
+            if !output_named_graphs {
+                member.graph = None;
+            }
+            // the fragmentation only stops receiving the members when it failed
+            tx_member
+                .send(member)
+                .map_err(|_| Error::Fragmentation("the fragmentation has stopped".to_string()))?;
             n_member_parsed += 1;
             if n_member_parsed % notice_frequency == 0 {
                 println!(
                     "--- {:} out of {:} ({:?}%)---",
                     n_member_parsed,
-                    data_injection_config.n_members,
-                    (n_member_parsed as f32 / data_injection_config.n_members as f32) * 100f32
+                    n_members,
+                    (n_member_parsed as f32 / n_members as f32) * 100f32
                 );
             }
         }
-        Ok(())
+        Ok(members_without_id)
+    };
+    let parsing_function = &mut |t: &rio_api::model::Triple,
+                                 graph_name: Option<&rio_api::model::GraphName>|
+     -> Result<(), Error> {
+        let members = member_extraction.add_triple(t, graph_name)?;
+        reject_members(member_extraction.take_rejected_members())?;
+        reject_members(send_members(members)?)
     };
 
     // we clone the values because we have to move them inside the thread
-    let highest_date = data_injection_config.highest_date.timestamp();
-    let lowest_date = data_injection_config.lowest_date.timestamp();
+    let config_date = |date: &Option<chrono::NaiveDateTime>| -> Result<i64, Error> {
+        let date = date.ok_or(Error::Config(
+            "the lowest and the highest date should be defined in the config".to_string(),
+        ))?;
+        to_time_axis(&date).map_err(Error::Config)
+    };
+    let highest_date = config_date(&data_injection_config.highest_date)?;
+    let lowest_date = config_date(&data_injection_config.lowest_date)?;
     let server_address = data_injection_config.server_address.clone();
-    let date_field = data_injection_config.date_field.clone();
-    let add_to_the_fragmentation = move || {
+    let date_field = data_injection_config.primary_date_field().clone();
+    let collection = if ldes {
+        Collection::from_server_address(&server_address, output_format).into_event_stream(
+            EventStream::new(
+                date_field.clone(),
+                data_injection_config.version_of_path.clone(),
+            ),
+        )
+    } else {
+        Collection::from_server_address(&server_address, output_format)
+    };
+    let collection = if let Some(shape) = &shape {
+        collection.with_shape(
+            shape
+                .iri
+                .clone()
+                .unwrap_or(format!("{server_address}{SHAPE_FILENAME}")),
+        )
+    } else {
+        collection
+    };
+    let report_folder = out_path.clone();
+    let add_to_the_fragmentation = move || -> Result<(), Error> {
         handle.block_on(async {
             let mut fragmentation = factory(
-                fragmentation_type,
-                n_fragments_first_row,
-                max_cache_element,
+                fragmentation_options,
                 &out_path,
                 highest_date,
                 lowest_date,
                 server_address,
                 date_field,
-                dept,
+                collection,
             )
-            .await;
+            .await?;
             let mut member_queue: VecDeque<Member> =
                 VecDeque::with_capacity(fragmentation.max_size_cache());
             loop {
@@ -147,28 +174,423 @@ pub fn parse_datadump(
                     member_queue.push_front(member);
                 } else {
                     for member in member_queue.iter() {
-                        fragmentation.insert(member).await;
+                        fragmentation.insert(member).await?;
                     }
-                    fragmentation.finalize().await;
-                    break;
+                    return fragmentation.finalize().await;
                 }
                 if let Some(member) = member_queue.pop_back() {
-                    fragmentation.insert(&member).await;
+                    fragmentation.insert(&member).await?;
                 }
             }
-        });
+        })
     };
 
     let worker = thread::spawn(add_to_the_fragmentation);
 
-    if large_file {
-        rio_turtle::TurtleParser::new(BufReader::new(file), None).parse_all(parsing_function)?;
-    } else {
-        rio_turtle::TurtleParser::new(read_to_string(data_dump_path)?.as_str().as_ref(), None)
-            .parse_all(parsing_function)?;
-    };
+    let parsing = parse_data_dumps(input, parsing_function).and_then(|_| {
+        let members = member_extraction.finalize();
+        reject_members(member_extraction.take_rejected_members())?;
+        reject_members(send_members(members)?)
+    });
     std::mem::drop(tx_member);
-    worker.join().unwrap();
+    // the error of the fragmentation comes first because it stops the parsing
+    worker
+        .join()
+        .map_err(|_| Error::Fragmentation("the fragmentation thread has panicked".to_string()))??;
+    parsing?;
+
+    let mut data_quality = member_extraction.data_quality().clone();
+    data_quality.reject_valid_members(&members_without_id_quality);
+    if n_rejected_members > 0 {
+        println!(
+            "{} members were rejected, they are written in {:?} with the reasons of their rejection",
+            n_rejected_members,
+            dead_letter_path.display()
+        );
+    }
+    create_data_quality_report(&data_quality, &report_folder)?;
+    let n_members_without_id = members_without_id_quality.n_invalid_members;
+    if n_members_without_id > 0 {
+        return Err(Error::Schema {
+            path: None,
+            message: format!(
+                "{n_members_without_id} members have no id, they are written in {:?}",
+                dead_letter_path.display()
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// The statistics of the valid members of the data dumps
+#[derive(Debug, Clone)]
+pub struct DataDumpStatistics {
+    pub n_members: usize,
+    pub lowest_date: chrono::NaiveDateTime,
+    pub highest_date: chrono::NaiveDateTime,
+    /// A sorted uniform sample of the dates of the members on the time axis
+    pub date_sample: Vec<i64>,
+}
+
+/// Stream the data dumps once to count the members, find the range of their dates
+/// and sample their dates
+pub fn pre_scan(
+    input: &InputOptions,
+    data_injection_config: &Config,
+) -> Result<DataDumpStatistics, Error> {
+    if input
+        .data_dump_paths
+        .iter()
+        .any(|data_dump_path| data_dump_path.as_os_str() == STDIN_PATH)
+    {
+        return Err(Error::Config(
+            "the standard input cannot be pre-scanned because it can only be read once".to_string(),
+        ));
+    }
+    let mut member_extraction = crate::member_extraction::factory(
+        input.member_extraction_type.clone(),
+        data_injection_config,
+        load_shape(data_injection_config)?,
+    )?;
+    let mut n_members = 0usize;
+    let mut lowest_date = i64::MAX;
+    let mut highest_date = i64::MIN;
+    // reservoir sampling, the seed is fixed so that the same data dump gives the same sample
+    let mut date_sample = Vec::with_capacity(DATE_SAMPLE_SIZE);
+    let mut random_generator = rand::rngs::StdRng::seed_from_u64(0);
+    let mut add_members = |members: Vec<Member>| {
+        for member in members {
+            n_members += 1;
+            lowest_date = lowest_date.min(member.date);
+            highest_date = highest_date.max(member.date);
+            if date_sample.len() < DATE_SAMPLE_SIZE {
+                date_sample.push(member.date);
+            } else {
+                let i = random_generator.gen_range(0..n_members);
+                if i < DATE_SAMPLE_SIZE {
+                    date_sample[i] = member.date;
+                }
+            }
+        }
+    };
+    parse_data_dumps(input, &mut |t, graph_name| {
+        add_members(member_extraction.add_triple(t, graph_name)?);
+        // the rejected members are only written during the fragmentation
+        member_extraction.take_rejected_members();
+        Ok(())
+    })?;
+    add_members(member_extraction.finalize());
+
+    if n_members == 0 {
+        return Err(Error::Schema {
+            path: None,
+            message: "no valid member was found during the pre-scan".to_string(),
+        });
+    }
+    date_sample.sort_unstable();
+    Ok(DataDumpStatistics {
+        n_members,
+        lowest_date: from_time_axis(lowest_date),
+        highest_date: from_time_axis(highest_date),
+        date_sample,
+    })
+}
+
+/// Append the rejected members to the dead letter file, each member is preceded by a comment
+/// with the reasons of its rejection. Return the number of members written.
+fn write_rejected_members(
+    dead_letter_path: &PathBuf,
+    rejected_members: Vec<RejectedMember>,
+    output_named_graphs: bool,
+) -> Result<usize, Error> {
+    if rejected_members.is_empty() {
+        return Ok(0);
+    }
+    let n_rejected_members = rejected_members.len();
+    let mut buffer = String::new();
+    for RejectedMember {
+        mut member,
+        violations,
+    } in rejected_members
+    {
+        if !output_named_graphs {
+            member.graph = None;
+        }
+        let id = if member.id.is_empty() {
+            "without id"
+        } else {
+            &member.id
+        };
+        buffer.push_str(&format!(
+            "# rejected member {id}: {}\n",
+            violations.join("; ")
+        ));
+        buffer.push_str(&member.to_string());
+    }
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(dead_letter_path)
+        .map_err(|e| Error::io(dead_letter_path, e))?;
+    file.write_all(buffer.as_bytes())
+        .map_err(|e| Error::io(dead_letter_path, e))?;
+    Ok(n_rejected_members)
+}
+
+fn load_shape(data_injection_config: &Config) -> Result<Option<NodeShape>, Error> {
+    Ok(match &data_injection_config.shape_path {
+        Some(shape_path) => Some(NodeShape::from_file(
+            shape_path,
+            &format!("{}{}", data_injection_config.server_address, SHAPE_FILENAME),
+        )?),
+        None => None,
+    })
+}
 
+/// Parse the data dumps one after the other, their blank nodes are scoped by data dump
+/// so that their labels do not collide. With a maximum number of syntax errors the malformed
+/// statements are skipped until there are more errors than this maximum.
+fn parse_data_dumps(
+    input: &InputOptions,
+    on_triple: &mut impl FnMut(
+        &rio_api::model::Triple,
+        Option<&rio_api::model::GraphName>,
+    ) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let scope_blank_nodes = input.data_dump_paths.len() > 1;
+    let mut syntax_error_budget = input.max_syntax_errors.map(SyntaxErrorBudget::new);
+    for (i, data_dump_path) in input.data_dump_paths.iter().enumerate() {
+        println!("Parsing the data dump {:?}", data_dump_path.display());
+        let mut reader = open_data_dump(data_dump_path)
+            .and_then(|reader| decompress(reader, data_dump_path))
+            .map_err(|e| Error::io(data_dump_path, e))?;
+        let reader: Box<dyn BufRead> = if input.large_file {
+            reader
+        } else {
+            let mut data = Vec::new();
+            reader
+                .read_to_end(&mut data)
+                .map_err(|e| Error::io(data_dump_path, e))?;
+            Box::new(Cursor::new(data))
+        };
+        let input_format = input
+            .input_format
+            .clone()
+            .or(InputFormat::from_extension(data_dump_path))
+            .unwrap_or(InputFormat::Turtle);
+        if scope_blank_nodes {
+            let blank_node_scope = format!("d{i}_");
+            parse_data_dump(
+                reader,
+                &input_format,
+                data_dump_path,
+                syntax_error_budget.as_mut(),
+                &mut |t, graph_name| {
+                    rename_blank_nodes_of_triple(
+                        t,
+                        graph_name,
+                        &|id| Some(format!("{blank_node_scope}{id}")),
+                        on_triple,
+                    )
+                },
+            )?;
+        } else {
+            parse_data_dump(
+                reader,
+                &input_format,
+                data_dump_path,
+                syntax_error_budget.as_mut(),
+                on_triple,
+            )?;
+        }
+    }
+    if let Some(syntax_error_budget) = syntax_error_budget {
+        if syntax_error_budget.n_syntax_errors() > 0 {
+            println!(
+                "{} malformed statements were skipped",
+                syntax_error_budget.n_syntax_errors()
+            );
+        }
+    }
     Ok(())
 }
+
+/// Parse a data dump, leniently if there is a budget of syntax errors
+fn parse_data_dump(
+    reader: impl BufRead,
+    input_format: &InputFormat,
+    data_dump_path: &Path,
+    syntax_error_budget: Option<&mut SyntaxErrorBudget>,
+    on_triple: &mut impl FnMut(
+        &rio_api::model::Triple,
+        Option<&rio_api::model::GraphName>,
+    ) -> Result<(), Error>,
+) -> Result<(), Error> {
+    match syntax_error_budget {
+        Some(syntax_error_budget) => parse_rdf_leniently(
+            reader,
+            input_format,
+            data_dump_path,
+            syntax_error_budget,
+            on_triple,
+        ),
+        None => parse_rdf(reader, input_format, on_triple),
+    }
+    .map_err(|e| e.in_file(data_dump_path))
+}
+
+/// Open a data dump, [STDIN_PATH] is the standard input
+fn open_data_dump(data_dump_path: &PathBuf) -> io::Result<Box<dyn BufRead>> {
+    if data_dump_path.as_os_str() == STDIN_PATH {
+        Ok(Box::new(io::stdin().lock()))
+    } else {
+        Ok(Box::new(BufReader::new(File::open(data_dump_path)?)))
+    }
+}
+
+/// Parse a RDF document, the triples of the formats without named graph are in the default graph
+fn parse_rdf(
+    reader: impl BufRead,
+    input_format: &InputFormat,
+    on_triple: &mut impl FnMut(
+        &rio_api::model::Triple,
+        Option<&rio_api::model::GraphName>,
+    ) -> Result<(), Error>,
+) -> Result<(), Error> {
+    match input_format {
+        InputFormat::Turtle => {
+            rio_turtle::TurtleParser::new(reader, None).parse_all(&mut |t| on_triple(&t, None))?
+        }
+        InputFormat::NTriples => {
+            rio_turtle::NTriplesParser::new(reader).parse_all(&mut |t| on_triple(&t, None))?
+        }
+        InputFormat::NQuads => {
+            rio_turtle::NQuadsParser::new(reader).parse_all(&mut |q| on_quad(q, on_triple))?
+        }
+        InputFormat::TriG => {
+            rio_turtle::TriGParser::new(reader, None).parse_all(&mut |q| on_quad(q, on_triple))?
+        }
+    };
+    Ok(())
+}
+
+/// Give a quad as a triple and its named graph
+pub fn on_quad(
+    q: rio_api::model::Quad,
+    on_triple: &mut impl FnMut(
+        &rio_api::model::Triple,
+        Option<&rio_api::model::GraphName>,
+    ) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let t = rio_api::model::Triple {
+        subject: q.subject,
+        predicate: q.predicate,
+        object: q.object,
+    };
+    on_triple(&t, q.graph_name.as_ref())
+}
+
+/// Rename the blank nodes of a triple, the blank nodes are kept when the renaming gives [None]
+pub fn rename_blank_nodes_of_triple(
+    t: &rio_api::model::Triple,
+    graph_name: Option<&rio_api::model::GraphName>,
+    rename: &impl Fn(&str) -> Option<String>,
+    on_triple: &mut impl FnMut(
+        &rio_api::model::Triple,
+        Option<&rio_api::model::GraphName>,
+    ) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let subject_id;
+    let subject = match t.subject {
+        rio_api::model::Subject::BlankNode(node) => match rename(node.id) {
+            Some(id) => {
+                subject_id = id;
+                rio_api::model::BlankNode { id: &subject_id }.into()
+            }
+            None => t.subject,
+        },
+        subject => subject,
+    };
+    let object_id;
+    let object = match t.object {
+        rio_api::model::Term::BlankNode(node) => match rename(node.id) {
+            Some(id) => {
+                object_id = id;
+                rio_api::model::BlankNode { id: &object_id }.into()
+            }
+            None => t.object,
+        },
+        object => object,
+    };
+    let graph_name_id;
+    let graph_name = match graph_name {
+        Some(rio_api::model::GraphName::BlankNode(node)) => match rename(node.id) {
+            Some(id) => {
+                graph_name_id = id;
+                Some(rio_api::model::BlankNode { id: &graph_name_id }.into())
+            }
+            None => graph_name.copied(),
+        },
+        graph_name => graph_name.copied(),
+    };
+    on_triple(
+        &rio_api::model::Triple {
+            subject,
+            predicate: t.predicate,
+            object,
+        },
+        graph_name.as_ref(),
+    )
+}
+
+/// The RDF serialization of the data dump
+#[derive(Clone, Debug)]
+pub enum InputFormat {
+    Turtle,
+    NTriples,
+    NQuads,
+    TriG,
+}
+
+impl InputFormat {
+    /// Guess the format from the extension of the data dump, ignoring the extension of the compression
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        let path = if Compression::from_extension(path).is_some() {
+            PathBuf::from(path.file_stem()?)
+        } else {
+            path.to_path_buf()
+        };
+        match path.extension()?.to_str()? {
+            "ttl" => Some(Self::Turtle),
+            "nt" => Some(Self::NTriples),
+            "nq" => Some(Self::NQuads),
+            "trig" => Some(Self::TriG),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<String> for InputFormat {
+    type Error = Error;
+
+    fn try_from(item: String) -> Result<Self, Self::Error> {
+        if item == "turtle" {
+            Ok(Self::Turtle)
+        } else if item == "nTriples" {
+            Ok(Self::NTriples)
+        } else if item == "nQuads" {
+            Ok(Self::NQuads)
+        } else if item == "trig" {
+            Ok(Self::TriG)
+        } else {
+            Err(Error::Config(format!("input format {item} not supported")))
+        }
+    }
+}
+
+/// The data dump path of the standard input
+pub const STDIN_PATH: &str = "-";
+const SHAPE_FILENAME: &str = "shape.ttl";
+const DEAD_LETTER_FILE_STEM: &str = "rejected";
+/// The number of dates sampled by the pre-scan to estimate their distribution
+const DATE_SAMPLE_SIZE: usize = 100_000;
@@ -0,0 +1,146 @@
+use crate::error::Error;
+use rio_api::parser::TriplesParser;
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+
+/// The SHACL node shape the members must respect, only the property shapes
+/// with a predicate path are supported.
+/// https://www.w3.org/TR/shacl/
+#[derive(Debug, Clone)]
+pub struct NodeShape {
+    /// The IRI of the shape, [None] if it is a blank node
+    pub iri: Option<String>,
+    /// The class the members must be an instance of
+    pub target_class: Option<String>,
+    pub properties: Vec<PropertyShape>,
+}
+
+/// A constraint on a property of the members
+#[derive(Debug, Clone)]
+pub struct PropertyShape {
+    /// The predicate of the property
+    pub path: String,
+    pub min_count: Option<usize>,
+    pub max_count: Option<usize>,
+    /// The datatype of the objects of the property
+    pub datatype: Option<String>,
+}
+
+impl NodeShape {
+    /// Read the first node shape of a Turtle shapes file, the relative IRIs are resolved with the base IRI
+    pub fn from_file(shape_path: &PathBuf, base_iri: &String) -> Result<Self, Error> {
+        let document = format!(
+            "@base <{base_iri}> .\n{}",
+            read_to_string(shape_path).map_err(|e| Error::io(shape_path, e))?
+        );
+        // the objects of the triples indexed by subject and predicate
+        let mut graph: HashMap<String, HashMap<String, Vec<String>>> = HashMap::new();
+        let mut node_shapes: Vec<String> = Vec::new();
+        rio_turtle::TurtleParser::new(document.as_bytes(), None)
+            .parse_all(&mut |t| -> Result<(), rio_turtle::TurtleError> {
+                let object = match t.object {
+                    rio_api::model::Term::NamedNode(node) => node.iri.to_string(),
+                    rio_api::model::Term::Literal(rio_api::model::Literal::Typed {
+                        value,
+                        datatype: _,
+                    }) => value.to_string(),
+                    _ => t.object.to_string(),
+                };
+                let subject = match t.subject {
+                    rio_api::model::Subject::NamedNode(node) => node.iri.to_string(),
+                    _ => t.subject.to_string(),
+                };
+                if t.predicate.iri == TYPE_VOCAB && object == SH_NODE_SHAPE_VOCAB {
+                    node_shapes.push(subject.clone());
+                }
+                graph
+                    .entry(subject)
+                    .or_default()
+                    .entry(t.predicate.iri.to_string())
+                    .or_default()
+                    .push(object);
+                Ok(())
+            })
+            .map_err(|e| match Error::from(e).in_file(shape_path) {
+                // the line of the base IRI is not part of the file
+                Error::RdfSyntax {
+                    path,
+                    line,
+                    column,
+                    message,
+                } => Error::RdfSyntax {
+                    path,
+                    line: line.map(|line| line.saturating_sub(1).max(1)),
+                    column,
+                    message,
+                },
+                error => error,
+            })?;
+        let invalid_shape = |message: String| {
+            Error::Config(format!(
+                "the shapes file {} is not valid: {message}",
+                shape_path.display()
+            ))
+        };
+
+        let node_shape = node_shapes
+            .first()
+            .ok_or(invalid_shape("it has no sh:NodeShape".to_string()))?;
+        let values = |subject: &String, predicate: &str| -> Vec<String> {
+            graph
+                .get(subject)
+                .and_then(|predicates| predicates.get(predicate))
+                .cloned()
+                .unwrap_or_default()
+        };
+        let first_value = |subject: &String, predicate: &str| -> Option<String> {
+            values(subject, predicate).first().cloned()
+        };
+        let count = |subject: &String, predicate: &str| -> Result<Option<usize>, Error> {
+            Ok(match first_value(subject, predicate) {
+                Some(count) => Some(count.parse().map_err(|_| {
+                    invalid_shape(format!("the count {count} of {subject} is not a number"))
+                })?),
+                None => None,
+            })
+        };
+
+        let mut properties = Vec::new();
+        for property in values(node_shape, SH_PROPERTY_VOCAB) {
+            let path = first_value(&property, SH_PATH_VOCAB).ok_or(invalid_shape(format!(
+                "the property shape {property} has no sh:path"
+            )))?;
+            if path.starts_with("_:") {
+                return Err(invalid_shape(format!(
+                    "the sh:path of {property} is not a predicate path"
+                )));
+            }
+            properties.push(PropertyShape {
+                path,
+                min_count: count(&property, SH_MIN_COUNT_VOCAB)?,
+                max_count: count(&property, SH_MAX_COUNT_VOCAB)?,
+                datatype: first_value(&property, SH_DATATYPE_VOCAB),
+            });
+        }
+
+        Ok(Self {
+            iri: if node_shape.starts_with("_:") {
+                None
+            } else {
+                Some(node_shape.clone())
+            },
+            target_class: first_value(node_shape, SH_TARGET_CLASS_VOCAB),
+            properties,
+        })
+    }
+}
+
+pub const TYPE_VOCAB: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const SH_NODE_SHAPE_VOCAB: &str = "http://www.w3.org/ns/shacl#NodeShape";
+const SH_PROPERTY_VOCAB: &str = "http://www.w3.org/ns/shacl#property";
+const SH_PATH_VOCAB: &str = "http://www.w3.org/ns/shacl#path";
+const SH_MIN_COUNT_VOCAB: &str = "http://www.w3.org/ns/shacl#minCount";
+const SH_MAX_COUNT_VOCAB: &str = "http://www.w3.org/ns/shacl#maxCount";
+const SH_DATATYPE_VOCAB: &str = "http://www.w3.org/ns/shacl#datatype";
+const SH_TARGET_CLASS_VOCAB: &str = "http://www.w3.org/ns/shacl#targetClass";
@@ -1,6 +1,3 @@
-use derive_getters;
-use derive_new;
-use rio_api;
 use std::fmt;
 
 #[allow(dead_code)]
@@ -87,6 +84,36 @@ impl Relation {
             relation_id,
         }
     }
+
+    /// Remove from a serialized node the relations toward the nodes,
+    /// the other statements of the node are kept as they are.
+    pub fn remove_from(node_document: &str, nodes: &[String]) -> String {
+        let node_statements: Vec<String> = nodes
+            .iter()
+            .map(|node| format!(" <{TREE_NODE_VOCAB}> <{node}> ."))
+            .collect();
+        let relation_ids: Vec<&str> = node_document
+            .lines()
+            .filter(|line| {
+                node_statements
+                    .iter()
+                    .any(|statement| line.ends_with(statement.as_str()))
+            })
+            .filter_map(|line| line.split_once(' ').map(|(relation_id, _)| relation_id))
+            .collect();
+        let mut resp = String::with_capacity(node_document.len());
+        for line in node_document.lines() {
+            let is_relation_statement = relation_ids.iter().any(|relation_id| {
+                line.starts_with(&format!("{relation_id} "))
+                    || line.ends_with(&format!(" {relation_id} ."))
+            });
+            if !is_relation_statement {
+                resp.push_str(line);
+                resp.push('\n');
+            }
+        }
+        resp
+    }
 }
 
 impl fmt::Display for Relation {
@@ -188,9 +215,179 @@ impl fmt::Display for Relation {
     }
 }
 
-const TREE_PATH_VOCAB: &'static str = "https://w3id.org/tree#path";
-const TREE_NODE_VOCAB: &'static str = "https://w3id.org/tree#node";
-const TREE_VALUE_VOCAB: &'static str = "https://w3id.org/tree#value";
-const TYPE_VOCAB: &'static str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
-const TREE_RELATION_VOCAB: &'static str = "https://w3id.org/tree#relation";
-const DATA_TIME_VOCAB: &'static str = "http://www.w3.org/2001/XMLSchema#dateTime";
+#[derive(derive_new::new, Clone, PartialEq, Eq, derive_getters::Getters, Debug)]
+/// A collection of members and the root node of the view describing it.
+/// https://treecg.github.io/specification/#Collection
+pub struct Collection {
+    /// The IRI of the collection.
+    iri: String,
+    /// The IRI of the root node of the view.
+    view: String,
+    /// The LDES description when the collection is published as an event stream.
+    event_stream: Option<EventStream>,
+    /// The SHACL shape the members respect.
+    shape: Option<String>,
+    /// The serialization of the nodes of the view.
+    output_format: OutputFormat,
+}
+
+impl Collection {
+    /// The collection is hosted in the root node `0` of the server.
+    pub fn from_server_address(server_address: &String, output_format: OutputFormat) -> Self {
+        let root_node = format!("0.{}", output_format.extension());
+        Self {
+            iri: format!("{server_address}{root_node}#collection"),
+            view: format!("{server_address}{root_node}"),
+            event_stream: None,
+            shape: None,
+            output_format,
+        }
+    }
+
+    /// The filename of a node of the view.
+    pub fn node_filename(&self, name: impl fmt::Display) -> String {
+        format!("{}.{}", name, self.output_format.extension())
+    }
+
+    /// Publish the collection as an `ldes:EventStream`.
+    pub fn into_event_stream(self, event_stream: EventStream) -> Self {
+        Self {
+            event_stream: Some(event_stream),
+            ..self
+        }
+    }
+
+    /// Publish the SHACL shape of the members with `tree:shape`.
+    pub fn with_shape(self, shape: String) -> Self {
+        Self {
+            shape: Some(shape),
+            ..self
+        }
+    }
+
+    /// The `tree:member` triple linking the collection to a member,
+    /// the id of the member is expected to be already serialized as an RDF term.
+    pub fn member_to_string(&self, member_id: &String) -> String {
+        format!("<{}> <{}> {} .\n", self.iri, TREE_MEMBER_VOCAB, member_id)
+    }
+}
+
+impl fmt::Display for Collection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut resp = String::new();
+        let collection_type = if self.event_stream.is_some() {
+            LDES_EVENT_STREAM_VOCAB
+        } else {
+            TREE_COLLECTION_VOCAB
+        };
+        let mut triples = vec![
+            rio_api::model::Triple {
+                subject: rio_api::model::NamedNode { iri: &self.iri }.into(),
+                predicate: rio_api::model::NamedNode { iri: TYPE_VOCAB },
+                object: rio_api::model::NamedNode {
+                    iri: collection_type,
+                }
+                .into(),
+            },
+            rio_api::model::Triple {
+                subject: rio_api::model::NamedNode { iri: &self.iri }.into(),
+                predicate: rio_api::model::NamedNode {
+                    iri: TREE_VIEW_VOCAB,
+                },
+                object: rio_api::model::NamedNode { iri: &self.view }.into(),
+            },
+            rio_api::model::Triple {
+                subject: rio_api::model::NamedNode { iri: &self.view }.into(),
+                predicate: rio_api::model::NamedNode { iri: TYPE_VOCAB },
+                object: rio_api::model::NamedNode {
+                    iri: TREE_NODE_TYPE_VOCAB,
+                }
+                .into(),
+            },
+        ];
+
+        if let Some(shape) = &self.shape {
+            triples.push(rio_api::model::Triple {
+                subject: rio_api::model::NamedNode { iri: &self.iri }.into(),
+                predicate: rio_api::model::NamedNode {
+                    iri: TREE_SHAPE_VOCAB,
+                },
+                object: rio_api::model::NamedNode { iri: shape }.into(),
+            });
+        }
+
+        if let Some(event_stream) = &self.event_stream {
+            triples.push(rio_api::model::Triple {
+                subject: rio_api::model::NamedNode { iri: &self.iri }.into(),
+                predicate: rio_api::model::NamedNode {
+                    iri: LDES_TIMESTAMP_PATH_VOCAB,
+                },
+                object: rio_api::model::NamedNode {
+                    iri: &event_stream.timestamp_path,
+                }
+                .into(),
+            });
+            if let Some(version_of_path) = &event_stream.version_of_path {
+                triples.push(rio_api::model::Triple {
+                    subject: rio_api::model::NamedNode { iri: &self.iri }.into(),
+                    predicate: rio_api::model::NamedNode {
+                        iri: LDES_VERSION_OF_PATH_VOCAB,
+                    },
+                    object: rio_api::model::NamedNode {
+                        iri: version_of_path,
+                    }
+                    .into(),
+                });
+            }
+        }
+
+        for triple in triples {
+            resp.push_str(&triple.to_string());
+            resp.push_str(" .\n");
+        }
+
+        write!(f, "{}", resp)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// The RDF serialization of the nodes of the view.
+pub enum OutputFormat {
+    Turtle,
+    /// The members are written in their named graph.
+    TriG,
+}
+
+impl OutputFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Turtle => "ttl",
+            OutputFormat::TriG => "trig",
+        }
+    }
+}
+
+#[derive(derive_new::new, Clone, PartialEq, Eq, derive_getters::Getters, Debug)]
+/// The properties of a collection published as a Linked Data Event Stream.
+/// https://semiceu.github.io/LinkedDataEventStreams/
+pub struct EventStream {
+    /// The path to the timestamp of a member.
+    timestamp_path: String,
+    /// The path to the object the member is a version of.
+    version_of_path: Option<String>,
+}
+
+const TREE_PATH_VOCAB: &str = "https://w3id.org/tree#path";
+const TREE_NODE_VOCAB: &str = "https://w3id.org/tree#node";
+const TREE_VALUE_VOCAB: &str = "https://w3id.org/tree#value";
+const TYPE_VOCAB: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const TREE_RELATION_VOCAB: &str = "https://w3id.org/tree#relation";
+const DATA_TIME_VOCAB: &str = "http://www.w3.org/2001/XMLSchema#dateTime";
+const TREE_MEMBER_VOCAB: &str = "https://w3id.org/tree#member";
+const TREE_SHAPE_VOCAB: &str = "https://w3id.org/tree#shape";
+const TREE_VIEW_VOCAB: &str = "https://w3id.org/tree#view";
+const TREE_COLLECTION_VOCAB: &str = "https://w3id.org/tree#Collection";
+const TREE_NODE_TYPE_VOCAB: &str = "https://w3id.org/tree#Node";
+const LDES_EVENT_STREAM_VOCAB: &str = "https://w3id.org/ldes#EventStream";
+const LDES_TIMESTAMP_PATH_VOCAB: &str = "https://w3id.org/ldes#timestampPath";
+const LDES_VERSION_OF_PATH_VOCAB: &str = "https://w3id.org/ldes#versionOfPath";